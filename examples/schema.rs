@@ -3,7 +3,13 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use cw_fractionalize::msg::{ExecuteMsg, GetCw20AddressResponse, InstantiateMsg, QueryMsg};
+use cw_fractionalize::msg::{
+  AssetClaimsResponse, AuctionResponse, BasketResponse, CollectionMode, CollectionsResponse,
+  ConfigResponse, CuratorResponse, DutchAuctionResponse, ExecuteMsg, FeeConfig, FeeCreditResponse,
+  GetCw20AddressResponse, InstantiateMsg, LockResponse, MigrateMsg, PauseState, ProposalResponse,
+  ProposalsResponse, QueryMsg, ReservePriceResponse, SaleResponse, ShotgunResponse,
+  TryGetCw20AddressResponse, VaultAssetsResponse, VaultInfo, VaultsResponse,
+};
 
 fn main() {
   let mut out_dir = current_dir().unwrap();
@@ -14,5 +20,27 @@ fn main() {
   export_schema(&schema_for!(InstantiateMsg), &out_dir);
   export_schema(&schema_for!(ExecuteMsg), &out_dir);
   export_schema(&schema_for!(QueryMsg), &out_dir);
+  export_schema(&schema_for!(MigrateMsg), &out_dir);
+  export_schema(&schema_for!(ConfigResponse), &out_dir);
+  export_schema(&schema_for!(FeeConfig), &out_dir);
+  export_schema(&schema_for!(FeeCreditResponse), &out_dir);
+  export_schema(&schema_for!(AssetClaimsResponse), &out_dir);
+  export_schema(&schema_for!(PauseState), &out_dir);
+  export_schema(&schema_for!(CollectionMode), &out_dir);
+  export_schema(&schema_for!(CollectionsResponse), &out_dir);
   export_schema(&schema_for!(GetCw20AddressResponse), &out_dir);
+  export_schema(&schema_for!(TryGetCw20AddressResponse), &out_dir);
+  export_schema(&schema_for!(LockResponse), &out_dir);
+  export_schema(&schema_for!(ReservePriceResponse), &out_dir);
+  export_schema(&schema_for!(AuctionResponse), &out_dir);
+  export_schema(&schema_for!(ShotgunResponse), &out_dir);
+  export_schema(&schema_for!(DutchAuctionResponse), &out_dir);
+  export_schema(&schema_for!(VaultInfo), &out_dir);
+  export_schema(&schema_for!(VaultsResponse), &out_dir);
+  export_schema(&schema_for!(VaultAssetsResponse), &out_dir);
+  export_schema(&schema_for!(BasketResponse), &out_dir);
+  export_schema(&schema_for!(CuratorResponse), &out_dir);
+  export_schema(&schema_for!(ProposalResponse), &out_dir);
+  export_schema(&schema_for!(ProposalsResponse), &out_dir);
+  export_schema(&schema_for!(SaleResponse), &out_dir);
 }
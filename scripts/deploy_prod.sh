@@ -3,6 +3,7 @@
 # takes several arguments:
 # 1: address to use for instantiating
 # 2: key to use as --from argument
+# 3: code ID of the cw20-base contract used for share tokens
 BINARY='junod'
 DENOM='ujuno'
 CHAIN_ID='juno-1'
@@ -26,10 +27,10 @@ CONTRACT_CODE=$($BINARY tx wasm store ./target/wasm32-unknown-unknown/release/cw
 
 echo "Stored: $CONTRACT_CODE"
 
-# instantiate the CW721
-INIT='{}'
+# instantiate the fractionalizer
+INIT="{\"owner\":\"$1\",\"cw20_code_id\":$3,\"buyout_denom\":\"$DENOM\",\"auction_duration\":604800,\"shotgun_duration\":604800,\"max_curator_fee_bps\":1000,\"governance\":{\"voting_period\":259200,\"quorum_bps\":2000,\"threshold_bps\":5000,\"reserve_quorum_bps\":5000}}"
 echo "$INIT" | jq .
-$BINARY tx wasm instantiate $CONTRACT_CODE "$INIT" --from "$2" --label $LABEL $TXFLAG --no-admin
+$BINARY tx wasm instantiate $CONTRACT_CODE "$INIT" --from "$2" --label $LABEL $TXFLAG --admin "$1"
 
 # get contract addr
 CONTRACT_ADDRESS=$($BINARY q wasm list-contract-by-code $CONTRACT_CODE --output json | jq -r '.contracts[-1]')
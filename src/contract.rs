@@ -1,37 +1,188 @@
+use std::collections::BTreeSet;
+
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-  from_binary, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Reply,
-  ReplyOn, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+  coin, coins, from_binary, to_binary, Addr, BankMsg, Binary, Coin, ContractInfoResponse, Deps,
+  DepsMut, Empty, Env, MessageInfo, Order, Reply, ReplyOn, StdError, StdResult, Storage, SubMsg,
+  SubMsgResult, Uint128, WasmMsg, WasmQuery,
 };
 
-use cw2::set_contract_version;
-use cw20::{Cw20Coin, Cw20ReceiveMsg};
-use cw20_base::msg::{ExecuteMsg as Cw20ExecuteMsg, InstantiateMsg as Cw20InstantiateMsg};
-use cw721::Cw721ReceiveMsg;
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{Cw20Coin, Cw20ReceiveMsg, Logo, MinterResponse};
+use cw20_base::msg::{
+  ExecuteMsg as Cw20ExecuteMsg, InstantiateMarketingInfo, InstantiateMsg as Cw20InstantiateMsg,
+};
+use cw721::{Cw721QueryMsg, Cw721ReceiveMsg, NftInfoResponse, OwnerOfResponse};
 use cw721_base::msg::ExecuteMsg as Cw721ExecuteMsg;
-use protobuf::Message;
+use cw_storage_plus::{Bound, Map};
+use cw_utils::{must_pay, one_coin, parse_reply_instantiate_data, PaymentError};
+use semver::Version;
+use serde::Deserialize;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, GetCw20AddressResponse, InstantiateMsg, QueryMsg, ReceiveMsg};
-use crate::response::MsgInstantiateContractResponse;
-use crate::state::{Config, CONFIG, CW20_NFT, NFT_CW20};
+use crate::msg::{
+  AssetClaimsResponse, AuctionResponse, BasketResponse, CollectionMode, CollectionsResponse,
+  ConfigResponse, CuratorResponse, DutchAuctionParams, DutchAuctionResponse, ExecuteMsg, FeeConfig,
+  FeeCreditResponse, FinalizeBasketMsg, GetCw20AddressResponse, GovernanceConfig, InstantiateMsg,
+  LockResponse, MigrateMsg, PauseState, PriceDecay, ProposalAction, ProposalResponse,
+  ProposalStatus, ProposalsResponse, QueryMsg, ReceiveMsg, ReservePriceResponse, SaleResponse,
+  ShareBackend, ShareTokenMarketing, ShareTokenParams, ShotgunResponse, SupplyLimits,
+  TokenFactoryMsg, TryGetCw20AddressResponse, UpdateConfigMsg, VaultAssetsResponse, VaultInfo,
+  VaultStatus, VaultsResponse, VoteOption,
+};
+use crate::state::{
+  auction_paused_seconds, next_basket_id, next_proposal_id, next_reply_id, next_share_subdenom,
+  proposals, vaults, Auction, Basket, Config, Curator, DutchAuction, Fees, PendingTransfer,
+  PendingVault, Proposal, ReservePriceVote, Sale, Shotgun, Vault, VaultAssets, ALLOWLIST,
+  ASSET_CLAIMS, AUCTIONS, AUCTION_PAUSE_CLOCK, BALLOTS, BASKETS, COLLECTION_MODE, CONFIG, CURATORS,
+  DENYLIST, DUTCH_AUCTIONS, FEE_CREDITS, LEGACY_CW20_NFT, LOCKS, NFT_CW20, PAUSED,
+  PENDING_TRANSFERS, PENDING_VAULTS, RESERVE_PRICE_OVERRIDES, RESERVE_PRICE_VOTES, SALES,
+  SHARE_DENOMS, SHOTGUNS, VAULT_ASSETS,
+};
+
+// Tokenfactory share vaults need custom messages, which every response has to allow for
+type Response = cosmwasm_std::Response<TokenFactoryMsg>;
+type CosmosMsg = cosmwasm_std::CosmosMsg<TokenFactoryMsg>;
 
 const CONTRACT_NAME: &str = "crates.io:cw-fractionalize";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-const INSTANTIATE_REPLY_ID: u64 = 1;
+// Each bid must beat the previous one by at least this percentage
+const MIN_BID_INCREMENT_PERCENT: u128 = 5;
+
+const BPS_DENOMINATOR: u64 = 10_000;
+
+// Caps what a deposited cw20 can burn when its transfer is paid out
+const ASSET_TRANSFER_GAS_LIMIT: u64 = 300_000;
+// Bounds the cw20 transfers, and so the gas, every payout of vault assets needs
+const MAX_DEPOSIT_TOKENS: usize = 10;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
   deps: DepsMut,
   _env: Env,
-  _info: MessageInfo,
-  _msg: InstantiateMsg,
+  info: MessageInfo,
+  msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
   set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-  Ok(Response::new())
+  let owner = match msg.owner.as_deref() {
+    Some(owner) => deps.api.addr_validate(owner)?,
+    None => info.sender,
+  };
+  let cw20_code_id = msg.cw20_code_id;
+  let config = build_config(deps.as_ref(), owner.clone(), msg)?;
+  CONFIG.save(deps.storage, &config)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "instantiate")
+      .add_attribute("owner", owner)
+      .add_attribute("cw20_code_id", cw20_code_id.to_string()),
+  )
+}
+
+fn build_config(deps: Deps, owner: Addr, msg: InstantiateMsg) -> Result<Config, ContractError> {
+  let fees = msg.fees.map(|fees| validate_fees(deps, fees)).transpose()?;
+  if msg.max_curator_fee_bps > BPS_DENOMINATOR {
+    return Err(ContractError::InvalidFee {});
+  }
+  validate_governance(&msg.governance)?;
+  let supply_limits = msg.supply_limits.unwrap_or_default();
+  validate_supply_limits(&supply_limits)?;
+  Ok(Config {
+    owner,
+    cw20_code_id: msg.cw20_code_id,
+    buyout_denom: msg.buyout_denom,
+    auction_duration: msg.auction_duration,
+    shotgun_duration: msg.shotgun_duration,
+    fees,
+    max_curator_fee_bps: msg.max_curator_fee_bps,
+    governance: msg.governance,
+    supply_limits,
+    denom_creation_fee: msg.denom_creation_fee.filter(|fee| !fee.amount.is_zero()),
+  })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+  let stored = get_contract_version(deps.storage)?;
+  if stored.contract != CONTRACT_NAME {
+    return Err(ContractError::WrongContract {
+      contract: stored.contract,
+    });
+  }
+  let stored_version = parse_version(&stored.version)?;
+  if stored_version > parse_version(CONTRACT_VERSION)? {
+    return Err(ContractError::CannotDowngrade {
+      stored: stored.version,
+      current: CONTRACT_VERSION.to_string(),
+    });
+  }
+
+  // each migration brings the state up to the version it is named after, so they run in order
+  if stored_version < Version::new(0, 1, 0) {
+    migrate_to_v0_1_0(deps.branch(), &env, &stored.version, msg.config)?;
+  }
+
+  set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+  Ok(
+    Response::new()
+      .add_attribute("action", "migrate")
+      .add_attribute("from_version", stored.version)
+      .add_attribute("to_version", CONTRACT_VERSION),
+  )
+}
+
+fn parse_version(version: &str) -> Result<Version, ContractError> {
+  Version::parse(version).map_err(|_| ContractError::InvalidVersion {
+    version: version.to_string(),
+  })
+}
+
+// 0.0.1 stored no usable config and recorded vaults only as `CW20_NFT` entries
+fn migrate_to_v0_1_0(
+  deps: DepsMut,
+  env: &Env,
+  from: &str,
+  config: Option<InstantiateMsg>,
+) -> Result<(), ContractError> {
+  let missing_config = || ContractError::MissingMigrationConfig {
+    from: from.to_string(),
+  };
+  let config = config.ok_or_else(missing_config)?;
+  let owner = deps
+    .api
+    .addr_validate(config.owner.as_deref().ok_or_else(missing_config)?)?;
+  let config = build_config(deps.as_ref(), owner, config)?;
+  CONFIG.save(deps.storage, &config)?;
+
+  let legacy = LEGACY_CW20_NFT
+    .range(deps.storage, None, None, Order::Ascending)
+    .collect::<StdResult<Vec<_>>>()?;
+  for (cw20_address, (collection, token_id)) in legacy {
+    vaults().save(
+      deps.storage,
+      &cw20_address,
+      &Vault {
+        collection,
+        token_ids: vec![token_id],
+        // the fractionalizer was not recorded
+        creator: env.contract.address.clone(),
+        created_at: env.block.time,
+        status: VaultStatus::Active,
+        mintable: false,
+        backend: ShareBackend::Cw20,
+        cw20_code_id: None,
+      },
+    )?;
+    LEGACY_CW20_NFT.remove(deps.storage, cw20_address);
+  }
+  Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -42,97 +193,845 @@ pub fn execute(
   msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
   match msg {
-    ExecuteMsg::ReceiveNft(msg) => handle_fractionalize(deps, info, msg),
+    ExecuteMsg::ReceiveNft(msg) => handle_fractionalize(deps, env, info, msg),
     ExecuteMsg::Receive(msg) => handle_unfractionalize(deps, info, env, msg),
+    ExecuteMsg::UpdateConfig(msg) => update_config(deps, info, msg),
+    ExecuteMsg::SetReservePrice { cw20, price } => set_reserve_price(deps, info, cw20, price),
+    ExecuteMsg::Bid { cw20 } => bid(deps, env, info, cw20),
+    ExecuteMsg::SettleAuction { cw20 } => settle_auction(deps, env, cw20),
+    ExecuteMsg::Unlock { cw20, amount } => unlock(deps, env, info, cw20, amount),
+    ExecuteMsg::StartShotgun {
+      cw20,
+      price_per_share,
+    } => start_shotgun(deps, env, info, cw20, price_per_share),
+    ExecuteMsg::CounterShotgun { cw20 } => counter_shotgun(deps, env, info, cw20),
+    ExecuteMsg::CompleteShotgun { cw20 } => complete_shotgun(deps, env, cw20),
+    ExecuteMsg::BuyNow { cw20 } => buy_now(deps, env, info, cw20),
+    ExecuteMsg::Deposit { cw20 } => deposit_native(deps, info, cw20),
+    ExecuteMsg::OpenBasket { collection } => open_basket(deps, info, collection),
+    ExecuteMsg::FinalizeBasket(msg) => finalize_basket(deps, env, info, msg),
+    ExecuteMsg::CancelBasket { basket_id } => cancel_basket(deps, info, basket_id),
+    ExecuteMsg::PayFee {} => pay_fee(deps, info),
+    ExecuteMsg::WithdrawFeeCredit {} => withdraw_fee_credit(deps, info),
+    ExecuteMsg::ClaimAsset { token } => claim_asset(deps, info, token),
+    ExecuteMsg::ClaimCuratorFee { cw20 } => claim_curator_fee(deps, env, info, cw20),
+    ExecuteMsg::Propose { cw20, action } => propose(deps, env, info, cw20, action),
+    ExecuteMsg::Vote { proposal_id, vote } => cast_vote(deps, env, info, proposal_id, vote),
+    ExecuteMsg::Execute { proposal_id } => execute_proposal(deps, env, proposal_id),
+    ExecuteMsg::AcceptSale { cw20 } => accept_sale(deps, info, cw20),
+    ExecuteMsg::Unfractionalize { recipient } => unfractionalize_native(deps, env, info, recipient),
+    ExecuteMsg::Pause {
+      fractionalize,
+      unfractionalize,
+      auctions,
+    } => pause(
+      deps,
+      env,
+      info,
+      PauseState {
+        fractionalize,
+        unfractionalize,
+        auctions,
+      },
+    ),
+    ExecuteMsg::SetCollectionMode { mode } => set_collection_mode(deps, info, mode),
+    ExecuteMsg::UpdateAllowlist { add, remove } => {
+      update_collections(deps, info, ALLOWLIST, "update_allowlist", add, remove)
+    }
+    ExecuteMsg::UpdateDenylist { add, remove } => {
+      update_collections(deps, info, DENYLIST, "update_denylist", add, remove)
+    }
+  }
+}
+
+pub fn update_config(
+  deps: DepsMut,
+  info: MessageInfo,
+  msg: UpdateConfigMsg,
+) -> Result<Response, ContractError> {
+  let mut config = CONFIG.load(deps.storage)?;
+  if info.sender != config.owner {
+    return Err(ContractError::Unauthorized {});
+  }
+
+  if let Some(owner) = msg.owner {
+    config.owner = deps.api.addr_validate(&owner)?;
+  }
+  if let Some(cw20_code_id) = msg.cw20_code_id {
+    config.cw20_code_id = cw20_code_id;
+  }
+  if let Some(buyout_denom) = msg.buyout_denom {
+    config.buyout_denom = buyout_denom;
+  }
+  if let Some(auction_duration) = msg.auction_duration {
+    config.auction_duration = auction_duration;
+  }
+  if let Some(shotgun_duration) = msg.shotgun_duration {
+    config.shotgun_duration = shotgun_duration;
+  }
+  if let Some(fees) = msg.fees {
+    config.fees = Some(validate_fees(deps.as_ref(), fees)?);
+  }
+  if let Some(max_curator_fee_bps) = msg.max_curator_fee_bps {
+    if max_curator_fee_bps > BPS_DENOMINATOR {
+      return Err(ContractError::InvalidFee {});
+    }
+    config.max_curator_fee_bps = max_curator_fee_bps;
+  }
+  if let Some(governance) = msg.governance {
+    validate_governance(&governance)?;
+    config.governance = governance;
+  }
+  if let Some(supply_limits) = msg.supply_limits {
+    validate_supply_limits(&supply_limits)?;
+    config.supply_limits = supply_limits;
+  }
+  if let Some(denom_creation_fee) = msg.denom_creation_fee {
+    config.denom_creation_fee = Some(denom_creation_fee).filter(|fee| !fee.amount.is_zero());
+  }
+  CONFIG.save(deps.storage, &config)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "update_config")
+      .add_attribute("owner", config.owner)
+      .add_attribute("cw20_code_id", config.cw20_code_id.to_string()),
+  )
+}
+
+pub fn pause(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  paused: PauseState,
+) -> Result<Response, ContractError> {
+  if info.sender != CONFIG.load(deps.storage)?.owner {
+    return Err(ContractError::Unauthorized {});
+  }
+  PAUSED.save(deps.storage, &paused)?;
+
+  let mut clock = AUCTION_PAUSE_CLOCK
+    .may_load(deps.storage)?
+    .unwrap_or_default();
+  match (paused.auctions, clock.paused_since) {
+    (true, None) => clock.paused_since = Some(env.block.time),
+    (false, Some(since)) => {
+      clock.paused_seconds += env.block.time.seconds().saturating_sub(since.seconds());
+      clock.paused_since = None;
+    }
+    _ => {}
+  }
+  AUCTION_PAUSE_CLOCK.save(deps.storage, &clock)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "pause")
+      .add_attribute("fractionalize", paused.fractionalize.to_string())
+      .add_attribute("unfractionalize", paused.unfractionalize.to_string())
+      .add_attribute("auctions", paused.auctions.to_string()),
+  )
+}
+
+fn ensure_unpaused(
+  storage: &dyn Storage,
+  flow: impl Fn(&PauseState) -> bool,
+) -> Result<(), ContractError> {
+  if flow(&PAUSED.may_load(storage)?.unwrap_or_default()) {
+    return Err(ContractError::Paused {});
+  }
+  Ok(())
+}
+
+pub fn set_collection_mode(
+  deps: DepsMut,
+  info: MessageInfo,
+  mode: CollectionMode,
+) -> Result<Response, ContractError> {
+  if info.sender != CONFIG.load(deps.storage)?.owner {
+    return Err(ContractError::Unauthorized {});
+  }
+  COLLECTION_MODE.save(deps.storage, &mode)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "set_collection_mode")
+      .add_attribute("mode", format!("{:?}", mode)),
+  )
+}
+
+fn update_collections(
+  deps: DepsMut,
+  info: MessageInfo,
+  list: Map<&Addr, Empty>,
+  action: &str,
+  add: Vec<String>,
+  remove: Vec<String>,
+) -> Result<Response, ContractError> {
+  if info.sender != CONFIG.load(deps.storage)?.owner {
+    return Err(ContractError::Unauthorized {});
+  }
+  for collection in add {
+    list.save(
+      deps.storage,
+      &deps.api.addr_validate(&collection)?,
+      &Empty {},
+    )?;
+  }
+  for collection in remove {
+    list.remove(deps.storage, &deps.api.addr_validate(&collection)?);
+  }
+
+  Ok(Response::new().add_attribute("action", action))
+}
+
+fn ensure_collection_allowed(
+  storage: &dyn Storage,
+  collection: &Addr,
+) -> Result<(), ContractError> {
+  let allowed = match COLLECTION_MODE
+    .may_load(storage)?
+    .unwrap_or(CollectionMode::Open)
+  {
+    CollectionMode::Open => true,
+    CollectionMode::Allowlist => ALLOWLIST.has(storage, collection),
+    CollectionMode::Denylist => !DENYLIST.has(storage, collection),
+  };
+  if !allowed {
+    return Err(ContractError::CollectionNotAllowed {
+      collection: collection.to_string(),
+    });
+  }
+  Ok(())
+}
+
+fn validate_fees(deps: Deps, fees: FeeConfig) -> Result<Fees, ContractError> {
+  if fees.fractionalize_bps > BPS_DENOMINATOR || fees.buyout_bps > BPS_DENOMINATOR {
+    return Err(ContractError::InvalidFee {});
+  }
+  Ok(Fees {
+    recipient: deps.api.addr_validate(&fees.recipient)?,
+    fractionalize_bps: fees.fractionalize_bps,
+    flat_fee: fees.flat_fee,
+    buyout_bps: fees.buyout_bps,
+  })
+}
+
+pub fn pay_fee(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+  if info.funds.is_empty() {
+    return Err(PaymentError::NoFunds {}.into());
+  }
+  credit_fee(deps.storage, &info.sender, &info.funds)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "pay_fee")
+      .add_attribute("payer", info.sender),
+  )
+}
+
+pub fn withdraw_fee_credit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+  let credit = fee_credit(deps.storage, &info.sender)?;
+  if credit.is_empty() {
+    return Err(ContractError::NoFeeCredit {});
+  }
+  for coin in &credit {
+    FEE_CREDITS.remove(deps.storage, (&info.sender, coin.denom.as_str()));
+  }
+
+  Ok(
+    Response::new()
+      .add_message(BankMsg::Send {
+        to_address: info.sender.to_string(),
+        amount: credit,
+      })
+      .add_attribute("action", "withdraw_fee_credit")
+      .add_attribute("payer", info.sender),
+  )
+}
+
+pub fn claim_asset(
+  deps: DepsMut,
+  info: MessageInfo,
+  token: String,
+) -> Result<Response, ContractError> {
+  let token = deps.api.addr_validate(&token)?;
+  let key = (&info.sender, &token);
+  let amount = ASSET_CLAIMS
+    .may_load(deps.storage, key)?
+    .ok_or(ContractError::NoAssetClaim {})?;
+  ASSET_CLAIMS.remove(deps.storage, key);
+
+  // a transfer failing again reverts the claim along with it
+  Ok(
+    Response::new()
+      .add_message(WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+          recipient: info.sender.to_string(),
+          amount,
+        })?,
+        funds: vec![],
+      })
+      .add_attribute("action", "claim_asset")
+      .add_attribute("recipient", info.sender)
+      .add_attribute("token", token)
+      .add_attribute("amount", amount),
+  )
+}
+
+fn fee_credit(storage: &dyn Storage, payer: &Addr) -> StdResult<Vec<Coin>> {
+  FEE_CREDITS
+    .prefix(payer)
+    .range(storage, None, None, Order::Ascending)
+    .filter(|item| !matches!(item, Ok((_, amount)) if amount.is_zero()))
+    .map(|item| item.map(|(denom, amount)| coin(amount.u128(), denom)))
+    .collect()
+}
+
+fn credit_fee(storage: &mut dyn Storage, payer: &Addr, funds: &[Coin]) -> StdResult<()> {
+  for coin in funds {
+    FEE_CREDITS.update(
+      storage,
+      (payer, coin.denom.as_str()),
+      |credit| -> StdResult<_> { Ok(credit.unwrap_or_default() + coin.amount) },
+    )?;
+  }
+  Ok(())
+}
+
+// Takes a fee out of the payer's prepaid credit
+fn charge_fee_credit(
+  storage: &mut dyn Storage,
+  payer: &Addr,
+  fee: &Coin,
+) -> Result<(), ContractError> {
+  let key = (payer, fee.denom.as_str());
+  let credit = FEE_CREDITS.may_load(storage, key)?.unwrap_or_default();
+  if credit < fee.amount {
+    return Err(ContractError::FeeNotPaid { fee: fee.clone() });
+  }
+  FEE_CREDITS.save(storage, key, &(credit - fee.amount))?;
+  Ok(())
+}
+
+// Charges the protocol fees of a new vault, issuing the fee share to the recipient
+fn charge_fractionalize_fees(
+  storage: &mut dyn Storage,
+  fees: &Fees,
+  creator: &Addr,
+  initial_balances: &mut Vec<Cw20Coin>,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+  let mut msgs = vec![];
+  if let Some(flat_fee) = fees.flat_fee.as_ref().filter(|fee| !fee.amount.is_zero()) {
+    charge_fee_credit(storage, creator, flat_fee)?;
+    msgs.push(CosmosMsg::Bank(BankMsg::Send {
+      to_address: fees.recipient.to_string(),
+      amount: vec![flat_fee.clone()],
+    }));
+  }
+
+  let mut supply = Uint128::zero();
+  for balance in initial_balances.iter() {
+    supply = supply.checked_add(balance.amount)?;
+  }
+  let fee = supply.multiply_ratio(fees.fractionalize_bps, BPS_DENOMINATOR);
+  if !fee.is_zero() {
+    match initial_balances
+      .iter_mut()
+      .find(|balance| balance.address == fees.recipient.as_str())
+    {
+      Some(balance) => balance.amount += fee,
+      None => initial_balances.push(Cw20Coin {
+        address: fees.recipient.to_string(),
+        amount: fee,
+      }),
+    }
   }
+  Ok(msgs)
 }
 
 pub fn fractionalize(
   deps: DepsMut,
-  collection: Addr,
-  token_id: String,
-  initial_balances: Vec<Cw20Coin>,
-  name: String,
-  symbol: String,
+  env: Env,
+  vault: PendingVault,
+  mut initial_balances: Vec<Cw20Coin>,
+  name: Option<String>,
+  symbol: Option<String>,
+  share_token: ShareTokenParams,
 ) -> Result<Response, ContractError> {
-  let exists = NFT_CW20.has(deps.storage, (collection.clone(), token_id.clone()));
+  ensure_unpaused(deps.storage, |paused| paused.fractionalize)?;
+  // checked again here since a collection can be denied while its basket is still open
+  ensure_collection_allowed(deps.storage, &vault.collection)?;
+  let exists = vault
+    .token_ids
+    .iter()
+    .any(|token_id| NFT_CW20.has(deps.storage, (vault.collection.clone(), token_id.clone())));
   if exists {
     return Err(ContractError::Exists {});
   }
+  // any contract can call `ReceiveNft`, so check the collection really handed the NFTs over
+  ensure_custody(deps.as_ref(), &env, &vault)?;
+
+  if let Some(params) = &vault.dutch_auction {
+    validate_dutch_auction(params)?;
+  }
+  // buyouts, curator fees and minting all go through the cw20 share token
+  if vault.backend == ShareBackend::TokenFactory
+    && (vault.dutch_auction.is_some()
+      || vault.curator_fee_bps > 0
+      || vault.mintable
+      || share_token != ShareTokenParams::default())
+  {
+    return Err(ContractError::UnsupportedByBackend {});
+  }
+  let (name, symbol) = share_token_names(deps.as_ref(), &vault, name, symbol);
+  let decimals = share_token.decimals.unwrap_or(6);
+  let label = share_token
+    .label
+    .unwrap_or_else(|| "fractionalize".to_string());
+  validate_share_token(&name, &symbol, decimals, &label)?;
+  let marketing = share_token_marketing(
+    deps.as_ref(),
+    &vault,
+    share_token.marketing.unwrap_or_default(),
+  )
+  .map(|marketing| InstantiateMarketingInfo {
+    project: marketing.project,
+    description: marketing.description,
+    marketing: Some(vault.creator.to_string()),
+    logo: marketing.logo,
+  });
+  let admin = if share_token.admin.unwrap_or_default() {
+    Some(env.contract.address.to_string())
+  } else {
+    None
+  };
+
+  let config = CONFIG.load(deps.storage)?;
+  validate_owners(deps.as_ref(), &initial_balances)?;
+  if vault.curator_fee_bps > config.max_curator_fee_bps {
+    return Err(ContractError::CuratorFeeTooHigh {
+      max: config.max_curator_fee_bps,
+    });
+  }
+  // the curator fee is minted by the fractionalizer as shares accrue
+  let mint = if vault.mintable || vault.curator_fee_bps > 0 {
+    Some(MinterResponse {
+      minter: env.contract.address.to_string(),
+      cap: vault.mint_cap,
+    })
+  } else {
+    None
+  };
+
+  let fee_msgs = match &config.fees {
+    Some(fees) => {
+      charge_fractionalize_fees(deps.storage, fees, &vault.creator, &mut initial_balances)?
+    }
+    None => vec![],
+  };
+  validate_supply(&config.supply_limits, &initial_balances)?;
+
+  if vault.backend == ShareBackend::TokenFactory {
+    let res = fractionalize_native(deps.storage, &env, &config, vault, initial_balances)?;
+    return Ok(res.add_messages(fee_msgs));
+  }
+
+  let reply_id = next_reply_id(deps.storage)?;
+  PENDING_VAULTS.save(deps.storage, reply_id, &vault)?;
+
+  Ok(
+    Response::new()
+      .add_messages(fee_msgs)
+      .add_submessage(SubMsg {
+        id: reply_id,
+        msg: CosmosMsg::Wasm(WasmMsg::Instantiate {
+          admin,
+          code_id: config.cw20_code_id,
+          msg: to_binary(&Cw20InstantiateMsg {
+            name,
+            symbol,
+            decimals,
+            initial_balances,
+            mint,
+            marketing,
+          })?,
+          funds: vec![],
+          label,
+        }),
+        gas_limit: None,
+        reply_on: ReplyOn::Success,
+      }),
+  )
+}
+
+// Checks that the collection reports the contract as the owner of every NFT. A collection can
+// answer `OwnerOf` however it likes, so this only catches honest collections that sent an NFT
+// without transferring it, not malicious ones.
+fn ensure_custody(deps: Deps, env: &Env, vault: &PendingVault) -> Result<(), ContractError> {
+  for token_id in &vault.token_ids {
+    let res: OwnerOfResponse = deps.querier.query_wasm_smart(
+      &vault.collection,
+      &Cw721QueryMsg::OwnerOf {
+        token_id: token_id.clone(),
+        include_expired: None,
+      },
+    )?;
+    if res.owner != env.contract.address {
+      return Err(ContractError::NftNotHeld {
+        token_id: token_id.clone(),
+      });
+    }
+  }
+  Ok(())
+}
+
+// Mints the shares as a tokenfactory denom, so the vault is recorded without waiting for a reply
+fn fractionalize_native(
+  storage: &mut dyn Storage,
+  env: &Env,
+  config: &Config,
+  vault: PendingVault,
+  initial_balances: Vec<Cw20Coin>,
+) -> Result<Response, ContractError> {
+  // the chain takes the creation fee from the contract's balance, so the creator pays it upfront
+  if let Some(fee) = &config.denom_creation_fee {
+    charge_fee_credit(storage, &vault.creator, fee)?;
+  }
+  let subdenom = next_share_subdenom(storage)?;
+  let denom = format!("factory/{}/{}", env.contract.address, subdenom);
+
+  let mut msgs = vec![CosmosMsg::Custom(TokenFactoryMsg::CreateDenom { subdenom })];
+  let mut supply = Uint128::zero();
+  for balance in initial_balances {
+    if balance.amount.is_zero() {
+      continue;
+    }
+    supply += balance.amount;
+    msgs.push(CosmosMsg::Custom(TokenFactoryMsg::MintTokens {
+      denom: denom.clone(),
+      amount: balance.amount,
+      mint_to_address: balance.address,
+    }));
+  }
+  SHARE_DENOMS.save(storage, &denom, &supply)?;
+
+  Ok(
+    register_vault(storage, env, vault, &denom, None)?
+      .add_messages(msgs)
+      .add_attribute("denom", denom),
+  )
+}
+
+// Partial cw721 metadata, as collections are free to extend it
+#[derive(Deserialize)]
+struct NftMetadata {
+  image: Option<String>,
+  description: Option<String>,
+}
+
+// Names the share token after the collection when the sender did not
+fn share_token_names(
+  deps: Deps,
+  vault: &PendingVault,
+  name: Option<String>,
+  symbol: Option<String>,
+) -> (String, String) {
+  let collection: Option<cw721::ContractInfoResponse> = if name.is_none() || symbol.is_none() {
+    deps
+      .querier
+      .query_wasm_smart(&vault.collection, &Cw721QueryMsg::ContractInfo {})
+      .ok()
+  } else {
+    None
+  };
+
+  let name = name.unwrap_or_else(|| {
+    let collection_name = collection
+      .as_ref()
+      .map(|info| info.name.as_str())
+      .unwrap_or("NFT");
+    let name = match vault.token_ids.as_slice() {
+      [token_id] => format!("{} #{}", collection_name, token_id),
+      _ => format!("{} basket", collection_name),
+    };
+    truncate(name, 50)
+  });
+  let symbol = symbol.unwrap_or_else(|| {
+    let collection_symbol: String = collection
+      .map(|info| info.symbol)
+      .unwrap_or_default()
+      .chars()
+      .filter(|c| c.is_ascii_alphabetic() || *c == '-')
+      .take(11)
+      .collect();
+    if collection_symbol.len() < 2 {
+      "fNFT".to_string()
+    } else {
+      format!("f{}", collection_symbol)
+    }
+  });
+  (name, symbol)
+}
+
+// Fills the description and logo the sender left out from the (first) NFT's metadata
+fn share_token_marketing(
+  deps: Deps,
+  vault: &PendingVault,
+  mut marketing: ShareTokenMarketing,
+) -> Option<ShareTokenMarketing> {
+  if marketing.description.is_none() || marketing.logo.is_none() {
+    let nft: Option<NftInfoResponse<Option<NftMetadata>>> = deps
+      .querier
+      .query_wasm_smart(
+        &vault.collection,
+        &Cw721QueryMsg::NftInfo {
+          token_id: vault.token_ids[0].clone(),
+        },
+      )
+      .ok();
+    if let Some(nft) = nft {
+      let token_uri = nft.token_uri;
+      let (image, description) = match nft.extension {
+        Some(metadata) => (metadata.image, metadata.description),
+        None => (None, None),
+      };
+      marketing.description = marketing
+        .description
+        .or_else(|| description.filter(|description| !description.is_empty()));
+      marketing.logo = marketing.logo.or_else(|| {
+        image
+          .or(token_uri)
+          .filter(|url| !url.is_empty())
+          .map(Logo::Url)
+      });
+    }
+  }
+
+  if marketing == ShareTokenMarketing::default() {
+    None
+  } else {
+    Some(marketing)
+  }
+}
+
+fn truncate(mut text: String, max_len: usize) -> String {
+  if text.len() > max_len {
+    let mut end = max_len;
+    while !text.is_char_boundary(end) {
+      end -= 1;
+    }
+    text.truncate(end);
+  }
+  text
+}
+
+// A vault without shares could never be redeemed or unfractionalized
+fn validate_owners(deps: Deps, owners: &[Cw20Coin]) -> Result<(), ContractError> {
+  if owners.is_empty() {
+    return Err(ContractError::NoOwners {});
+  }
+
+  let mut addresses = BTreeSet::new();
+  let mut supply = Uint128::zero();
+  for owner in owners {
+    let address =
+      deps
+        .api
+        .addr_validate(&owner.address)
+        .map_err(|_| ContractError::InvalidOwner {
+          address: owner.address.clone(),
+        })?;
+    if !addresses.insert(address) {
+      return Err(ContractError::DuplicateOwner {
+        address: owner.address.clone(),
+      });
+    }
+    supply = supply.checked_add(owner.amount).map_err(StdError::from)?;
+  }
+
+  if supply.is_zero() {
+    return Err(ContractError::ZeroSupply {});
+  }
+  Ok(())
+}
+
+// Bounds the supply actually minted, protocol fee shares included
+fn validate_supply(
+  supply_limits: &SupplyLimits,
+  initial_balances: &[Cw20Coin],
+) -> Result<(), ContractError> {
+  let mut supply = Uint128::zero();
+  for balance in initial_balances {
+    supply = supply.checked_add(balance.amount).map_err(StdError::from)?;
+  }
+  if let Some(min) = supply_limits.min {
+    if supply < min {
+      return Err(ContractError::SupplyTooLow { min });
+    }
+  }
+  if let Some(max) = supply_limits.max {
+    if supply > max {
+      return Err(ContractError::SupplyTooHigh { max });
+    }
+  }
+  Ok(())
+}
+
+// Mirrors the checks cw20-base runs on instantiation, so a bad token fails before the NFT is held
+fn validate_share_token(
+  name: &str,
+  symbol: &str,
+  decimals: u8,
+  label: &str,
+) -> Result<(), ContractError> {
+  if name.len() < 3 || name.len() > 50 {
+    return Err(ContractError::InvalidName {});
+  }
+  let symbol_chars = symbol
+    .bytes()
+    .all(|byte| byte.is_ascii_alphabetic() || byte == b'-');
+  if symbol.len() < 3 || symbol.len() > 12 || !symbol_chars {
+    return Err(ContractError::InvalidSymbol {});
+  }
+  if decimals > 18 {
+    return Err(ContractError::InvalidDecimals {});
+  }
+  if label.trim().is_empty() {
+    return Err(ContractError::InvalidLabel {});
+  }
+  Ok(())
+}
+
+pub fn unfractionalize_native(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  recipient: String,
+) -> Result<Response, ContractError> {
+  let recipient = deps.api.addr_validate(&recipient)?;
+  let shares = one_coin(&info)?;
+  let supply = SHARE_DENOMS
+    .may_load(deps.storage, &shares.denom)?
+    .ok_or(ContractError::NotFractionalized {})?;
+  let mut vault = vaults()
+    .may_load(deps.storage, &shares.denom)?
+    .ok_or(ContractError::NotFractionalized {})?;
+  ensure_active(&vault)?;
+  if shares.amount != supply {
+    return Err(ContractError::InsufficientFunds {});
+  }
 
-  // TODO: is there another way to pass NFT data to reply fn
-  CONFIG.save(
+  close_vault(
     deps.storage,
-    &Config {
-      last_nft_fractionalized: (collection, token_id),
-    },
+    &shares.denom,
+    &mut vault,
+    VaultStatus::Unfractionalized,
   )?;
+  SHARE_DENOMS.save(deps.storage, &shares.denom, &Uint128::zero())?;
 
-  Ok(Response::new().add_submessage(SubMsg {
-    id: INSTANTIATE_REPLY_ID,
-    msg: CosmosMsg::Wasm(WasmMsg::Instantiate {
-      admin: None,
-      code_id: INSTANTIATE_REPLY_ID,
-      msg: to_binary(&Cw20InstantiateMsg {
-        name,
-        symbol,
-        decimals: 6,
-        initial_balances,
-        mint: None,
-        marketing: None,
-      })?,
-      funds: vec![],
-      label: "fractionalize".to_string(),
-    }),
-    gas_limit: None,
-    reply_on: ReplyOn::Success,
-  }))
+  let assets = VAULT_ASSETS
+    .may_load(deps.storage, &shares.denom)?
+    .unwrap_or_default();
+  VAULT_ASSETS.remove(deps.storage, &shares.denom);
+
+  Ok(
+    Response::new()
+      .add_messages(transfer_nfts_msgs(
+        &vault.collection,
+        recipient.as_str(),
+        vault.token_ids,
+      )?)
+      .add_message(CosmosMsg::Custom(TokenFactoryMsg::BurnTokens {
+        denom: shares.denom.clone(),
+        amount: shares.amount,
+        burn_from_address: env.contract.address.to_string(),
+      }))
+      .add_submessages(asset_msgs(deps.storage, assets, &recipient)?)
+      .add_attribute("action", "unfractionalize")
+      .add_attribute("denom", shares.denom),
+  )
 }
 
 pub fn handle_unfractionalize(
   deps: DepsMut,
   info: MessageInfo,
-  _env: Env,
+  env: Env,
   wrapped: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
   let msg: ReceiveMsg = from_binary(&wrapped.msg)?;
   match msg {
     ReceiveMsg::Unfractionalize { recipient } => {
-      unfractionalize(deps, info.sender, recipient, wrapped.amount)
+      unfractionalize(deps, env, info.sender, recipient, wrapped.amount)
+    }
+    ReceiveMsg::Lock {} => lock(deps, info.sender, wrapped.sender, wrapped.amount),
+    ReceiveMsg::Redeem { recipient } => {
+      ensure_unpaused(deps.storage, |paused| paused.unfractionalize)?;
+      redeem(deps, info.sender, recipient, wrapped.amount)
     }
+    ReceiveMsg::Deposit { cw20 } => deposit_cw20(deps, info.sender, cw20, wrapped.amount),
     _ => Err(ContractError::Unauthorized {}),
   }
 }
 
 pub fn handle_fractionalize(
   deps: DepsMut,
+  env: Env,
   info: MessageInfo,
   wrapped: Cw721ReceiveMsg,
 ) -> Result<Response, ContractError> {
+  ensure_collection_allowed(deps.storage, &info.sender)?;
   let msg: ReceiveMsg = from_binary(&wrapped.msg)?;
   match msg {
     ReceiveMsg::Fractionalize {
       owners,
       name,
       symbol,
-    } => fractionalize(deps, info.sender, wrapped.token_id, owners, name, symbol),
+      dutch_auction,
+      curator_fee_bps,
+      mintable,
+      mint_cap,
+      share_token,
+      backend,
+    } => {
+      let vault = PendingVault {
+        collection: info.sender,
+        token_ids: vec![wrapped.token_id],
+        creator: deps.api.addr_validate(&wrapped.sender)?,
+        dutch_auction,
+        curator_fee_bps: curator_fee_bps.unwrap_or_default(),
+        mintable: mintable.unwrap_or_default() || mint_cap.is_some(),
+        mint_cap,
+        backend: backend.unwrap_or(ShareBackend::Cw20),
+      };
+      fractionalize(
+        deps,
+        env,
+        vault,
+        owners,
+        name,
+        symbol,
+        share_token.unwrap_or_default(),
+      )
+    }
+    ReceiveMsg::DepositToBasket { basket_id } => deposit_to_basket(deps, info, wrapped, basket_id),
     _ => Err(ContractError::Unauthorized {}),
   }
 }
 
 pub fn unfractionalize(
   deps: DepsMut,
+  env: Env,
   cw20_address: Addr,
   recipient: String,
   amount: Uint128,
 ) -> Result<Response, ContractError> {
-  let data = CW20_NFT.may_load(deps.storage, cw20_address.to_string())?;
-  if data.is_none() {
-    return Err(ContractError::NotFractionalized {});
-  }
-
-  let (nft_address, token_id) = data.unwrap();
+  let recipient = deps.api.addr_validate(&recipient)?;
+  let mut vault = load_vault(deps.storage, &cw20_address)?;
+  ensure_active(&vault)?;
+  verify_share_token(deps.as_ref(), &env, &cw20_address, &vault)?;
 
+  // shares minted since (curator fees, governance) have to be returned as well
   let cw20_info: cw20::TokenInfoResponse = deps.querier.query_wasm_smart(
     cw20_address.clone(),
     &cw20_base::msg::QueryMsg::TokenInfo {},
@@ -141,70 +1040,1854 @@ pub fn unfractionalize(
     return Err(ContractError::InsufficientFunds {});
   }
 
-  NFT_CW20.remove(deps.storage, (nft_address.clone(), token_id.clone()));
-  CW20_NFT.remove(deps.storage, cw20_address.to_string());
+  close_vault(
+    deps.storage,
+    cw20_address.as_str(),
+    &mut vault,
+    VaultStatus::Unfractionalized,
+  )?;
+
+  // whoever holds the whole supply also takes everything deposited into the vault
+  let assets = VAULT_ASSETS
+    .may_load(deps.storage, cw20_address.as_str())?
+    .unwrap_or_default();
+  VAULT_ASSETS.remove(deps.storage, cw20_address.as_str());
 
   Ok(
     Response::new()
-      .add_submessage(SubMsg::new(WasmMsg::Execute {
-        contract_addr: nft_address.to_string(),
-        msg: to_binary(&Cw721ExecuteMsg::<Empty, Empty>::TransferNft {
-          recipient,
-          token_id,
-        })?,
-        funds: vec![],
-      }))
-      .add_submessage(SubMsg::new(WasmMsg::Execute {
-        contract_addr: cw20_address.to_string(),
-        msg: to_binary(&Cw20ExecuteMsg::Burn {
-          amount: cw20_info.total_supply,
-        })?,
+      .add_messages(transfer_nfts_msgs(
+        &vault.collection,
+        recipient.as_str(),
+        vault.token_ids,
+      )?)
+      .add_message(burn_msg(&cw20_address, cw20_info.total_supply)?)
+      .add_submessages(asset_msgs(deps.storage, assets, &recipient)?),
+  )
+}
+
+// Makes sure the share token is still the one this contract instantiated, so its supply can only
+// change through this contract
+fn verify_share_token(
+  deps: Deps,
+  env: &Env,
+  cw20_address: &Addr,
+  vault: &Vault,
+) -> Result<(), ContractError> {
+  let info: ContractInfoResponse = deps.querier.query(
+    &WasmQuery::ContractInfo {
+      contract_addr: cw20_address.to_string(),
+    }
+    .into(),
+  )?;
+  let contract = env.contract.address.as_str();
+  let code_id_matches = !matches!(vault.cw20_code_id, Some(code_id) if code_id != info.code_id);
+  let admin_matches = !matches!(info.admin, Some(admin) if admin != contract);
+  if info.creator != contract || !code_id_matches || !admin_matches {
+    return Err(ContractError::InvalidShareToken {});
+  }
+
+  let minter: Option<MinterResponse> = deps
+    .querier
+    .query_wasm_smart(cw20_address, &cw20_base::msg::QueryMsg::Minter {})?;
+  let minter_matches = match minter {
+    Some(minter) => vault.mintable && minter.minter == contract,
+    None => true,
+  };
+  if !minter_matches {
+    return Err(ContractError::InvalidShareToken {});
+  }
+  Ok(())
+}
+
+pub fn open_basket(
+  deps: DepsMut,
+  info: MessageInfo,
+  collection: String,
+) -> Result<Response, ContractError> {
+  let collection = deps.api.addr_validate(&collection)?;
+  let basket_id = next_basket_id(deps.storage)?;
+  BASKETS.save(
+    deps.storage,
+    basket_id,
+    &Basket {
+      creator: info.sender.clone(),
+      collection: collection.clone(),
+      token_ids: vec![],
+    },
+  )?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "open_basket")
+      .add_attribute("basket_id", basket_id.to_string())
+      .add_attribute("creator", info.sender)
+      .add_attribute("collection", collection),
+  )
+}
+
+pub fn deposit_to_basket(
+  deps: DepsMut,
+  info: MessageInfo,
+  wrapped: Cw721ReceiveMsg,
+  basket_id: u64,
+) -> Result<Response, ContractError> {
+  let mut basket = load_basket(deps.storage, basket_id)?;
+  if wrapped.sender != basket.creator {
+    return Err(ContractError::Unauthorized {});
+  }
+  if info.sender != basket.collection {
+    return Err(ContractError::WrongCollection {
+      collection: basket.collection.into_string(),
+    });
+  }
+
+  basket.token_ids.push(wrapped.token_id.clone());
+  BASKETS.save(deps.storage, basket_id, &basket)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "deposit_to_basket")
+      .add_attribute("basket_id", basket_id.to_string())
+      .add_attribute("token_id", wrapped.token_id),
+  )
+}
+
+pub fn finalize_basket(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  msg: FinalizeBasketMsg,
+) -> Result<Response, ContractError> {
+  let basket_id = msg.basket_id;
+  let basket = load_basket(deps.storage, basket_id)?;
+  if info.sender != basket.creator {
+    return Err(ContractError::Unauthorized {});
+  }
+  if basket.token_ids.is_empty() {
+    return Err(ContractError::EmptyBasket {});
+  }
+  credit_fee(deps.storage, &info.sender, &info.funds)?;
+
+  BASKETS.remove(deps.storage, basket_id);
+  let vault = PendingVault {
+    collection: basket.collection,
+    token_ids: basket.token_ids,
+    creator: basket.creator,
+    dutch_auction: msg.dutch_auction,
+    curator_fee_bps: msg.curator_fee_bps.unwrap_or_default(),
+    mintable: msg.mintable.unwrap_or_default() || msg.mint_cap.is_some(),
+    mint_cap: msg.mint_cap,
+    backend: msg.backend.unwrap_or(ShareBackend::Cw20),
+  };
+  fractionalize(
+    deps,
+    env,
+    vault,
+    msg.owners,
+    msg.name,
+    msg.symbol,
+    msg.share_token.unwrap_or_default(),
+  )
+}
+
+pub fn cancel_basket(
+  deps: DepsMut,
+  info: MessageInfo,
+  basket_id: u64,
+) -> Result<Response, ContractError> {
+  let basket = load_basket(deps.storage, basket_id)?;
+  if info.sender != basket.creator {
+    return Err(ContractError::Unauthorized {});
+  }
+
+  BASKETS.remove(deps.storage, basket_id);
+
+  Ok(
+    Response::new()
+      .add_messages(transfer_nfts_msgs(
+        &basket.collection,
+        basket.creator.as_str(),
+        basket.token_ids,
+      )?)
+      .add_attribute("action", "cancel_basket")
+      .add_attribute("basket_id", basket_id.to_string()),
+  )
+}
+
+pub fn deposit_native(
+  deps: DepsMut,
+  info: MessageInfo,
+  cw20: String,
+) -> Result<Response, ContractError> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let vault = load_vault(deps.storage, &cw20_address)?;
+  if vault.status == VaultStatus::Unfractionalized {
+    return Err(ContractError::NotFractionalized {});
+  }
+  if info.funds.is_empty() {
+    return Err(PaymentError::NoFunds {}.into());
+  }
+
+  let mut assets = VAULT_ASSETS
+    .may_load(deps.storage, cw20_address.as_str())?
+    .unwrap_or_default();
+  for coin in info.funds.iter().filter(|c| !c.amount.is_zero()) {
+    assets.add_native(coin.clone());
+  }
+  VAULT_ASSETS.save(deps.storage, cw20_address.as_str(), &assets)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "deposit")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("depositor", info.sender),
+  )
+}
+
+pub fn deposit_cw20(
+  deps: DepsMut,
+  token: Addr,
+  cw20: String,
+  amount: Uint128,
+) -> Result<Response, ContractError> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  if token == cw20_address {
+    return Err(ContractError::InvalidDeposit {});
+  }
+  let vault = load_vault(deps.storage, &cw20_address)?;
+  if vault.status == VaultStatus::Unfractionalized {
+    return Err(ContractError::NotFractionalized {});
+  }
+
+  let mut assets = VAULT_ASSETS
+    .may_load(deps.storage, cw20_address.as_str())?
+    .unwrap_or_default();
+  let known = assets.cw20.iter().any(|coin| coin.address == token);
+  if !known && assets.cw20.len() >= MAX_DEPOSIT_TOKENS {
+    return Err(ContractError::TooManyDepositTokens {
+      max: MAX_DEPOSIT_TOKENS,
+    });
+  }
+  assets.add_cw20(token.clone(), amount);
+  VAULT_ASSETS.save(deps.storage, cw20_address.as_str(), &assets)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "deposit")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("token", token)
+      .add_attribute("amount", amount),
+  )
+}
+
+pub fn lock(
+  deps: DepsMut,
+  cw20_address: Addr,
+  sender: String,
+  amount: Uint128,
+) -> Result<Response, ContractError> {
+  ensure_active(&load_vault(deps.storage, &cw20_address)?)?;
+  let holder = deps.api.addr_validate(&sender)?;
+
+  let key = (cw20_address.as_str(), &holder);
+  let mut lock = LOCKS.may_load(deps.storage, key)?.unwrap_or_default();
+  lock.amount += amount;
+  LOCKS.save(deps.storage, key, &lock)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "lock")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("holder", holder)
+      .add_attribute("amount", amount),
+  )
+}
+
+pub fn unlock(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  cw20: String,
+  amount: Uint128,
+) -> Result<Response, ContractError> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let key = (cw20_address.as_str(), &info.sender);
+  let mut lock = LOCKS.may_load(deps.storage, key)?.unwrap_or_default();
+  if env.block.time < lock.unlocks_at {
+    return Err(ContractError::SharesLocked {
+      unlocks_at: lock.unlocks_at,
+    });
+  }
+  if amount > lock.amount {
+    return Err(ContractError::InsufficientFunds {});
+  }
+
+  lock.amount -= amount;
+  if lock.amount.is_zero() {
+    LOCKS.remove(deps.storage, key);
+    RESERVE_PRICE_VOTES.remove(deps.storage, key);
+  } else {
+    LOCKS.save(deps.storage, key, &lock)?;
+  }
+
+  Ok(
+    Response::new()
+      .add_message(WasmMsg::Execute {
+        contract_addr: cw20_address.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+          recipient: info.sender.to_string(),
+          amount,
+        })?,
+        funds: vec![],
+      })
+      .add_attribute("action", "unlock")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("holder", info.sender)
+      .add_attribute("amount", amount),
+  )
+}
+
+pub fn claim_curator_fee(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  cw20: String,
+) -> Result<Response, ContractError> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  ensure_active(&load_vault(deps.storage, &cw20_address)?)?;
+  let mut curator = CURATORS
+    .may_load(deps.storage, cw20_address.as_str())?
+    .ok_or(ContractError::NoCurator {})?;
+  if info.sender != curator.address {
+    return Err(ContractError::Unauthorized {});
+  }
+
+  let cw20_info: cw20::TokenInfoResponse = deps.querier.query_wasm_smart(
+    cw20_address.clone(),
+    &cw20_base::msg::QueryMsg::TokenInfo {},
+  )?;
+  let amount = curator.accrued(cw20_info.total_supply, env.block.time);
+  curator.last_claim = env.block.time;
+  CURATORS.save(deps.storage, cw20_address.as_str(), &curator)?;
+
+  let mut res = Response::new();
+  if !amount.is_zero() {
+    res = res.add_message(mint_msg(&cw20_address, &curator.address, amount)?);
+  }
+
+  Ok(
+    res
+      .add_attribute("action", "claim_curator_fee")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("curator", curator.address)
+      .add_attribute("amount", amount),
+  )
+}
+
+fn validate_supply_limits(supply_limits: &SupplyLimits) -> Result<(), ContractError> {
+  if let (Some(min), Some(max)) = (supply_limits.min, supply_limits.max) {
+    if min > max {
+      return Err(ContractError::InvalidSupplyLimits {});
+    }
+  }
+  Ok(())
+}
+
+fn validate_governance(governance: &GovernanceConfig) -> Result<(), ContractError> {
+  if governance.voting_period == 0
+    || governance.quorum_bps > BPS_DENOMINATOR
+    || governance.threshold_bps > BPS_DENOMINATOR
+    || governance.reserve_quorum_bps > BPS_DENOMINATOR
+  {
+    return Err(ContractError::InvalidGovernance {});
+  }
+  Ok(())
+}
+
+pub fn propose(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  cw20: String,
+  action: ProposalAction,
+) -> Result<Response, ContractError> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let vault = load_vault(deps.storage, &cw20_address)?;
+  ensure_active(&vault)?;
+  if locked_shares(deps.storage, &cw20_address, &info.sender)?.is_zero() {
+    return Err(ContractError::NoVotingPower {});
+  }
+  validate_action(deps.as_ref(), &cw20_address, &vault, &action)?;
+
+  let config = CONFIG.load(deps.storage)?;
+  let id = next_proposal_id(deps.storage)?;
+  let proposal = Proposal {
+    cw20: cw20_address.clone(),
+    proposer: info.sender.clone(),
+    action,
+    yes_votes: Uint128::zero(),
+    no_votes: Uint128::zero(),
+    abstain_votes: Uint128::zero(),
+    end_time: env.block.time.plus_seconds(config.governance.voting_period),
+    status: ProposalStatus::Open,
+  };
+  proposals().save(deps.storage, id, &proposal)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "propose")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("proposal_id", id.to_string())
+      .add_attribute("proposer", info.sender)
+      .add_attribute("end_time", proposal.end_time.seconds().to_string()),
+  )
+}
+
+fn validate_action(
+  deps: Deps,
+  cw20_address: &Addr,
+  vault: &Vault,
+  action: &ProposalAction,
+) -> Result<(), ContractError> {
+  match action {
+    ProposalAction::SetReservePrice { .. } => {}
+    ProposalAction::UpdateCurator { curator } => {
+      deps.api.addr_validate(curator)?;
+      if !CURATORS.has(deps.storage, cw20_address.as_str()) {
+        return Err(ContractError::NoCurator {});
+      }
+    }
+    ProposalAction::ApproveSale { buyer, .. } => {
+      deps.api.addr_validate(buyer)?;
+    }
+    ProposalAction::NftAction { msg } => {
+      // only cw721-base messages that cannot move an NFT, since the contract holds those of every
+      // vault of the collection and other messages may mean anything to it
+      let allowed = match from_binary(msg) {
+        Ok(Cw721ExecuteMsg::<Empty, Empty>::Revoke { token_id, .. }) => {
+          vault.token_ids.contains(&token_id)
+        }
+        Ok(Cw721ExecuteMsg::RevokeAll { .. }) => true,
+        _ => false,
+      };
+      if !allowed {
+        return Err(ContractError::ForbiddenNftAction {});
+      }
+    }
+    ProposalAction::MintShares { recipient, .. } => {
+      deps.api.addr_validate(recipient)?;
+      if !vault.mintable {
+        return Err(ContractError::NotMintable {});
+      }
+    }
+  }
+  Ok(())
+}
+
+pub fn cast_vote(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  proposal_id: u64,
+  vote: VoteOption,
+) -> Result<Response, ContractError> {
+  let mut proposal = load_proposal(deps.storage, proposal_id)?;
+  if proposal.status != ProposalStatus::Open {
+    return Err(ContractError::ProposalClosed {});
+  }
+  if env.block.time >= proposal.end_time {
+    return Err(ContractError::VotingEnded {});
+  }
+  if BALLOTS.has(deps.storage, (proposal_id, &info.sender)) {
+    return Err(ContractError::AlreadyVoted {});
+  }
+
+  // the voting shares stay locked until the proposal ends, so they cannot vote twice
+  let key = (proposal.cw20.as_str(), &info.sender);
+  let mut lock = LOCKS.may_load(deps.storage, key)?.unwrap_or_default();
+  if lock.amount.is_zero() {
+    return Err(ContractError::NoVotingPower {});
+  }
+  lock.unlocks_at = lock.unlocks_at.max(proposal.end_time);
+  LOCKS.save(deps.storage, key, &lock)?;
+  BALLOTS.save(deps.storage, (proposal_id, &info.sender), &lock.amount)?;
+
+  match vote {
+    VoteOption::Yes => proposal.yes_votes += lock.amount,
+    VoteOption::No => proposal.no_votes += lock.amount,
+    VoteOption::Abstain => proposal.abstain_votes += lock.amount,
+  }
+  proposals().save(deps.storage, proposal_id, &proposal)?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "vote")
+      .add_attribute("proposal_id", proposal_id.to_string())
+      .add_attribute("voter", info.sender)
+      .add_attribute("weight", lock.amount),
+  )
+}
+
+pub fn execute_proposal(
+  mut deps: DepsMut,
+  env: Env,
+  proposal_id: u64,
+) -> Result<Response, ContractError> {
+  let mut proposal = load_proposal(deps.storage, proposal_id)?;
+  if proposal.status != ProposalStatus::Open {
+    return Err(ContractError::ProposalClosed {});
+  }
+  if env.block.time < proposal.end_time {
+    return Err(ContractError::VotingNotEnded {});
+  }
+
+  // a sold or unfractionalized vault can no longer run any action, so its proposals fail
+  let vault = load_vault(deps.storage, &proposal.cw20)?;
+  let closed = matches!(
+    vault.status,
+    VaultStatus::Sold | VaultStatus::Unfractionalized
+  );
+  let passed = !closed && tally_passed(deps.as_ref(), &proposal)?;
+
+  let mut res = Response::new();
+  if passed {
+    ensure_active(&vault)?;
+    res = run_action(
+      deps.branch(),
+      &env,
+      &proposal.cw20,
+      &vault,
+      &proposal.action,
+    )?;
+    proposal.status = ProposalStatus::Executed;
+  } else {
+    proposal.status = ProposalStatus::Rejected;
+  }
+  proposals().save(deps.storage, proposal_id, &proposal)?;
+
+  Ok(
+    res
+      .add_attribute("action", "execute_proposal")
+      .add_attribute("proposal_id", proposal_id.to_string())
+      .add_attribute("passed", passed.to_string()),
+  )
+}
+
+// Whether a proposal met the quorum and threshold once voting ended
+fn tally_passed(deps: Deps, proposal: &Proposal) -> Result<bool, ContractError> {
+  let config = CONFIG.load(deps.storage)?;
+  let cw20_info: cw20::TokenInfoResponse = deps.querier.query_wasm_smart(
+    proposal.cw20.clone(),
+    &cw20_base::msg::QueryMsg::TokenInfo {},
+  )?;
+  let turnout = proposal.yes_votes + proposal.no_votes + proposal.abstain_votes;
+  let quorum = cw20_info
+    .total_supply
+    .multiply_ratio(config.governance.quorum_bps, BPS_DENOMINATOR);
+  let threshold = (proposal.yes_votes + proposal.no_votes)
+    .multiply_ratio(config.governance.threshold_bps, BPS_DENOMINATOR);
+  Ok(!proposal.yes_votes.is_zero() && turnout >= quorum && proposal.yes_votes >= threshold)
+}
+
+fn run_action(
+  deps: DepsMut,
+  env: &Env,
+  cw20_address: &Addr,
+  vault: &Vault,
+  action: &ProposalAction,
+) -> Result<Response, ContractError> {
+  let mut res = Response::new();
+  match action {
+    ProposalAction::SetReservePrice { price } => {
+      RESERVE_PRICE_OVERRIDES.save(deps.storage, cw20_address.as_str(), price)?;
+    }
+    ProposalAction::UpdateCurator { curator } => {
+      let mut current = CURATORS
+        .may_load(deps.storage, cw20_address.as_str())?
+        .ok_or(ContractError::NoCurator {})?;
+      // the outgoing curator keeps the fee accrued so far
+      let cw20_info: cw20::TokenInfoResponse = deps.querier.query_wasm_smart(
+        cw20_address.clone(),
+        &cw20_base::msg::QueryMsg::TokenInfo {},
+      )?;
+      let accrued = current.accrued(cw20_info.total_supply, env.block.time);
+      if !accrued.is_zero() {
+        res = res.add_message(mint_msg(cw20_address, &current.address, accrued)?);
+      }
+      current.address = deps.api.addr_validate(curator)?;
+      current.last_claim = env.block.time;
+      CURATORS.save(deps.storage, cw20_address.as_str(), &current)?;
+    }
+    ProposalAction::ApproveSale { buyer, price } => {
+      SALES.save(
+        deps.storage,
+        cw20_address.as_str(),
+        &Sale {
+          buyer: deps.api.addr_validate(buyer)?,
+          price: *price,
+        },
+      )?;
+    }
+    ProposalAction::NftAction { msg } => {
+      res = res.add_message(WasmMsg::Execute {
+        contract_addr: vault.collection.to_string(),
+        msg: msg.clone(),
+        funds: vec![],
+      });
+    }
+    ProposalAction::MintShares { recipient, amount } => {
+      let recipient = deps.api.addr_validate(recipient)?;
+      res = res.add_message(mint_msg(cw20_address, &recipient, *amount)?);
+    }
+  }
+  Ok(res)
+}
+
+pub fn accept_sale(
+  deps: DepsMut,
+  info: MessageInfo,
+  cw20: String,
+) -> Result<Response, ContractError> {
+  ensure_unpaused(deps.storage, |paused| paused.auctions)?;
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let sale = SALES
+    .may_load(deps.storage, cw20_address.as_str())?
+    .ok_or(ContractError::NoSale {})?;
+  if info.sender != sale.buyer {
+    return Err(ContractError::Unauthorized {});
+  }
+  let mut vault = load_vault(deps.storage, &cw20_address)?;
+  ensure_active(&vault)?;
+
+  let config = CONFIG.load(deps.storage)?;
+  let paid = must_pay(&info, &config.buyout_denom)?;
+  if paid != sale.price {
+    return Err(ContractError::IncorrectPayment {
+      expected: sale.price,
+    });
+  }
+
+  close_vault(
+    deps.storage,
+    cw20_address.as_str(),
+    &mut vault,
+    VaultStatus::Sold,
+  )?;
+  let fee_msgs = add_proceeds(
+    deps.storage,
+    &config,
+    &cw20_address,
+    coin(sale.price.u128(), &config.buyout_denom),
+  )?;
+
+  Ok(
+    Response::new()
+      .add_messages(transfer_nfts_msgs(
+        &vault.collection,
+        sale.buyer.as_str(),
+        vault.token_ids,
+      )?)
+      .add_messages(fee_msgs)
+      .add_attribute("action", "accept_sale")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("buyer", sale.buyer)
+      .add_attribute("price", sale.price),
+  )
+}
+
+fn load_proposal(storage: &dyn Storage, proposal_id: u64) -> Result<Proposal, ContractError> {
+  proposals()
+    .may_load(storage, proposal_id)?
+    .ok_or(ContractError::ProposalNotFound { id: proposal_id })
+}
+
+pub fn set_reserve_price(
+  deps: DepsMut,
+  info: MessageInfo,
+  cw20: String,
+  price: Uint128,
+) -> Result<Response, ContractError> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  ensure_active(&load_vault(deps.storage, &cw20_address)?)?;
+
+  let weight = locked_shares(deps.storage, &cw20_address, &info.sender)?;
+  if weight.is_zero() {
+    return Err(ContractError::NoVotingPower {});
+  }
+
+  RESERVE_PRICE_VOTES.save(
+    deps.storage,
+    (cw20_address.as_str(), &info.sender),
+    &ReservePriceVote { price },
+  )?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "set_reserve_price")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("voter", info.sender)
+      .add_attribute("price", price)
+      .add_attribute("weight", weight),
+  )
+}
+
+fn locked_shares(storage: &dyn Storage, cw20_address: &Addr, holder: &Addr) -> StdResult<Uint128> {
+  Ok(
+    LOCKS
+      .may_load(storage, (cw20_address.as_str(), holder))?
+      .map(|lock| lock.amount)
+      .unwrap_or_default(),
+  )
+}
+
+// Median of the voted reserve prices, weighted by the voters' locked shares
+pub fn reserve_price(deps: Deps, cw20_address: &Addr) -> StdResult<ReservePriceResponse> {
+  let mut votes = vec![];
+  let mut voted_shares = Uint128::zero();
+  for vote in RESERVE_PRICE_VOTES.prefix(cw20_address.as_str()).range(
+    deps.storage,
+    None,
+    None,
+    Order::Ascending,
+  ) {
+    let (voter, vote) = vote?;
+    let weight = locked_shares(deps.storage, cw20_address, &voter)?;
+    if !weight.is_zero() {
+      votes.push((vote.price, weight));
+      voted_shares += weight;
+    }
+  }
+
+  // a price set by a passed proposal has already met the governance quorum
+  if let Some(price) = RESERVE_PRICE_OVERRIDES.may_load(deps.storage, cw20_address.as_str())? {
+    return Ok(ReservePriceResponse {
+      price: Some(price),
+      voted_shares,
+      quorum_reached: true,
+    });
+  }
+
+  votes.sort_by_key(|(price, _)| *price);
+  let mut cumulative = Uint128::zero();
+  let price = votes.into_iter().find_map(|(price, weight)| {
+    cumulative += weight;
+    if cumulative >= voted_shares - cumulative {
+      Some(price)
+    } else {
+      None
+    }
+  });
+
+  let config = CONFIG.load(deps.storage)?;
+  let cw20_info: cw20::TokenInfoResponse = deps.querier.query_wasm_smart(
+    cw20_address.clone(),
+    &cw20_base::msg::QueryMsg::TokenInfo {},
+  )?;
+  let quorum_reached = price.is_some()
+    && voted_shares.full_mul(BPS_DENOMINATOR)
+      >= cw20_info
+        .total_supply
+        .full_mul(config.governance.reserve_quorum_bps);
+
+  Ok(ReservePriceResponse {
+    price,
+    voted_shares,
+    quorum_reached,
+  })
+}
+
+pub fn bid(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  cw20: String,
+) -> Result<Response, ContractError> {
+  ensure_unpaused(deps.storage, |paused| paused.auctions)?;
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let mut vault = load_vault(deps.storage, &cw20_address)?;
+  if vault.status != VaultStatus::Auction {
+    ensure_active(&vault)?;
+  }
+
+  let config = CONFIG.load(deps.storage)?;
+  let current = AUCTIONS.may_load(deps.storage, cw20_address.as_str())?;
+  let denom = current
+    .as_ref()
+    .map_or(&config.buyout_denom, |auction| &auction.denom);
+  let amount = must_pay(&info, denom)?;
+
+  let mut res = Response::new();
+  let auction = match current {
+    None => {
+      let reserve_price = reserve_price(deps.as_ref(), &cw20_address)?;
+      let reserve = reserve_price
+        .price
+        .ok_or(ContractError::NoReservePrice {})?;
+      if !reserve_price.quorum_reached {
+        return Err(ContractError::ReserveQuorumNotReached {
+          min_bps: config.governance.reserve_quorum_bps,
+        });
+      }
+      if amount < reserve {
+        return Err(ContractError::BidTooLow { min_bid: reserve });
+      }
+
+      vault.status = VaultStatus::Auction;
+      vaults().save(deps.storage, cw20_address.as_str(), &vault)?;
+
+      Auction {
+        bidder: info.sender,
+        amount,
+        denom: config.buyout_denom,
+        end_time: env.block.time.plus_seconds(config.auction_duration),
+        paused_seconds: auction_paused_seconds(deps.storage, env.block.time)?,
+      }
+    }
+    Some(mut auction) => {
+      auction.resume(auction_paused_seconds(deps.storage, env.block.time)?);
+      if env.block.time >= auction.end_time {
+        return Err(ContractError::AuctionEnded {});
+      }
+
+      let increment = auction
+        .amount
+        .multiply_ratio(MIN_BID_INCREMENT_PERCENT, 100u128)
+        .max(Uint128::new(1));
+      let min_bid = auction.amount + increment;
+      if amount < min_bid {
+        return Err(ContractError::BidTooLow { min_bid });
+      }
+
+      // refund the outbid bidder
+      res = res.add_message(BankMsg::Send {
+        to_address: auction.bidder.to_string(),
+        amount: coins(auction.amount.u128(), &auction.denom),
+      });
+
+      Auction {
+        bidder: info.sender,
+        amount,
+        denom: auction.denom,
+        end_time: auction.end_time,
+        paused_seconds: auction.paused_seconds,
+      }
+    }
+  };
+  AUCTIONS.save(deps.storage, cw20_address.as_str(), &auction)?;
+
+  Ok(
+    res
+      .add_attribute("action", "bid")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("bidder", auction.bidder)
+      .add_attribute("amount", auction.amount)
+      .add_attribute("end_time", auction.end_time.seconds().to_string()),
+  )
+}
+
+pub fn settle_auction(deps: DepsMut, env: Env, cw20: String) -> Result<Response, ContractError> {
+  ensure_unpaused(deps.storage, |paused| paused.auctions)?;
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let mut auction = AUCTIONS
+    .may_load(deps.storage, cw20_address.as_str())?
+    .ok_or(ContractError::NoAuction {})?;
+  auction.resume(auction_paused_seconds(deps.storage, env.block.time)?);
+  if env.block.time < auction.end_time {
+    return Err(ContractError::AuctionNotEnded {});
+  }
+
+  let mut vault = load_vault(deps.storage, &cw20_address)?;
+
+  AUCTIONS.remove(deps.storage, cw20_address.as_str());
+  close_vault(
+    deps.storage,
+    cw20_address.as_str(),
+    &mut vault,
+    VaultStatus::Sold,
+  )?;
+  let config = CONFIG.load(deps.storage)?;
+  let fee_msgs = add_proceeds(
+    deps.storage,
+    &config,
+    &cw20_address,
+    coin(auction.amount.u128(), &auction.denom),
+  )?;
+
+  Ok(
+    Response::new()
+      .add_messages(transfer_nfts_msgs(
+        &vault.collection,
+        auction.bidder.as_str(),
+        vault.token_ids,
+      )?)
+      .add_messages(fee_msgs)
+      .add_attribute("action", "settle_auction")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("winner", auction.bidder)
+      .add_attribute("amount", auction.amount),
+  )
+}
+
+pub fn redeem(
+  deps: DepsMut,
+  cw20_address: Addr,
+  recipient: String,
+  amount: Uint128,
+) -> Result<Response, ContractError> {
+  let vault = load_vault(deps.storage, &cw20_address)?;
+  if vault.status != VaultStatus::Sold {
+    ensure_active(&vault)?;
+  }
+  let mut assets = VAULT_ASSETS
+    .may_load(deps.storage, cw20_address.as_str())?
+    .ok_or(ContractError::NothingToRedeem {})?;
+  let recipient = deps.api.addr_validate(&recipient)?;
+
+  let cw20_info: cw20::TokenInfoResponse = deps.querier.query_wasm_smart(
+    cw20_address.clone(),
+    &cw20_base::msg::QueryMsg::TokenInfo {},
+  )?;
+  if vault.status == VaultStatus::Active {
+    if assets.is_empty() {
+      return Err(ContractError::NothingToRedeem {});
+    }
+    if amount == cw20_info.total_supply {
+      return Err(ContractError::RedeemWholeSupply {});
+    }
+  }
+
+  let payout = assets.split(amount, cw20_info.total_supply);
+  if amount == cw20_info.total_supply {
+    VAULT_ASSETS.remove(deps.storage, cw20_address.as_str());
+  } else {
+    VAULT_ASSETS.save(deps.storage, cw20_address.as_str(), &assets)?;
+  }
+
+  Ok(
+    Response::new()
+      .add_message(burn_msg(&cw20_address, amount)?)
+      .add_submessages(asset_msgs(deps.storage, payout, &recipient)?)
+      .add_attribute("action", "redeem")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("recipient", recipient)
+      .add_attribute("amount", amount),
+  )
+}
+
+pub fn start_shotgun(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  cw20: String,
+  price_per_share: Uint128,
+) -> Result<Response, ContractError> {
+  ensure_unpaused(deps.storage, |paused| paused.auctions)?;
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let mut vault = load_vault(deps.storage, &cw20_address)?;
+  ensure_active(&vault)?;
+
+  let balance: cw20::BalanceResponse = deps.querier.query_wasm_smart(
+    cw20_address.clone(),
+    &cw20_base::msg::QueryMsg::Balance {
+      address: info.sender.to_string(),
+    },
+  )?;
+  let shares = balance.balance;
+  if shares.is_zero() {
+    return Err(ContractError::NoShares {});
+  }
+
+  let cw20_info: cw20::TokenInfoResponse = deps.querier.query_wasm_smart(
+    cw20_address.clone(),
+    &cw20_base::msg::QueryMsg::TokenInfo {},
+  )?;
+  if shares == cw20_info.total_supply {
+    return Err(ContractError::OwnsAllShares {});
+  }
+
+  let config = CONFIG.load(deps.storage)?;
+  let escrow = price_per_share.checked_mul(cw20_info.total_supply - shares)?;
+  let paid = must_pay(&info, &config.buyout_denom)?;
+  if paid != escrow {
+    return Err(ContractError::IncorrectPayment { expected: escrow });
+  }
+
+  let shotgun = Shotgun {
+    initiator: info.sender.clone(),
+    price_per_share,
+    shares,
+    escrow,
+    denom: config.buyout_denom,
+    deadline: env.block.time.plus_seconds(config.shotgun_duration),
+    paused_seconds: auction_paused_seconds(deps.storage, env.block.time)?,
+  };
+  SHOTGUNS.save(deps.storage, cw20_address.as_str(), &shotgun)?;
+  vault.status = VaultStatus::Shotgun;
+  vaults().save(deps.storage, cw20_address.as_str(), &vault)?;
+
+  Ok(
+    Response::new()
+      .add_message(WasmMsg::Execute {
+        contract_addr: cw20_address.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+          owner: info.sender.to_string(),
+          recipient: env.contract.address.to_string(),
+          amount: shares,
+        })?,
         funds: vec![],
-      })),
+      })
+      .add_attribute("action", "start_shotgun")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("initiator", info.sender)
+      .add_attribute("price_per_share", price_per_share)
+      .add_attribute("shares", shares)
+      .add_attribute("deadline", shotgun.deadline.seconds().to_string()),
   )
 }
 
-// Reply callback triggered from cw721 contract instantiation
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
-  let data = msg.result.unwrap().data.unwrap();
-  let res: MsgInstantiateContractResponse = Message::parse_from_bytes(data.as_slice())
-    .map_err(|_| StdError::parse_err("MsgInstantiateContractResponse", "failed to parse data"))?;
-  let cw20_address = res.get_address().to_string();
+pub fn counter_shotgun(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  cw20: String,
+) -> Result<Response, ContractError> {
+  ensure_unpaused(deps.storage, |paused| paused.auctions)?;
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let mut shotgun = SHOTGUNS
+    .may_load(deps.storage, cw20_address.as_str())?
+    .ok_or(ContractError::NoShotgun {})?;
+  shotgun.resume(auction_paused_seconds(deps.storage, env.block.time)?);
+  if env.block.time >= shotgun.deadline {
+    return Err(ContractError::ShotgunExpired {});
+  }
+
+  let price = shotgun.price_per_share.checked_mul(shotgun.shares)?;
+  let paid = must_pay(&info, &shotgun.denom)?;
+  if paid != price {
+    return Err(ContractError::IncorrectPayment { expected: price });
+  }
+
+  SHOTGUNS.remove(deps.storage, cw20_address.as_str());
+  let mut vault = load_vault(deps.storage, &cw20_address)?;
+  vault.status = VaultStatus::Active;
+  vaults().save(deps.storage, cw20_address.as_str(), &vault)?;
+
+  // the initiator is bought out at their own price and gets their escrow back
+  Ok(
+    Response::new()
+      .add_message(WasmMsg::Execute {
+        contract_addr: cw20_address.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+          recipient: info.sender.to_string(),
+          amount: shotgun.shares,
+        })?,
+        funds: vec![],
+      })
+      .add_message(BankMsg::Send {
+        to_address: shotgun.initiator.to_string(),
+        amount: coins((price + shotgun.escrow).u128(), shotgun.denom),
+      })
+      .add_attribute("action", "counter_shotgun")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("initiator", shotgun.initiator)
+      .add_attribute("buyer", info.sender)
+      .add_attribute("shares", shotgun.shares),
+  )
+}
+
+pub fn complete_shotgun(deps: DepsMut, env: Env, cw20: String) -> Result<Response, ContractError> {
+  ensure_unpaused(deps.storage, |paused| paused.auctions)?;
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let mut shotgun = SHOTGUNS
+    .may_load(deps.storage, cw20_address.as_str())?
+    .ok_or(ContractError::NoShotgun {})?;
+  shotgun.resume(auction_paused_seconds(deps.storage, env.block.time)?);
+  if env.block.time < shotgun.deadline {
+    return Err(ContractError::ShotgunNotExpired {});
+  }
+
+  let mut vault = load_vault(deps.storage, &cw20_address)?;
+
+  SHOTGUNS.remove(deps.storage, cw20_address.as_str());
+  close_vault(
+    deps.storage,
+    cw20_address.as_str(),
+    &mut vault,
+    VaultStatus::Sold,
+  )?;
+  // the remaining share holders redeem the escrow at the offered price per share
+  let config = CONFIG.load(deps.storage)?;
+  let fee_msgs = add_proceeds(
+    deps.storage,
+    &config,
+    &cw20_address,
+    coin(shotgun.escrow.u128(), shotgun.denom),
+  )?;
+
+  Ok(
+    Response::new()
+      .add_messages(transfer_nfts_msgs(
+        &vault.collection,
+        shotgun.initiator.as_str(),
+        vault.token_ids,
+      )?)
+      .add_messages(fee_msgs)
+      .add_message(burn_msg(&cw20_address, shotgun.shares)?)
+      .add_attribute("action", "complete_shotgun")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("initiator", shotgun.initiator)
+      .add_attribute("escrow", shotgun.escrow),
+  )
+}
+
+pub fn buy_now(
+  deps: DepsMut,
+  env: Env,
+  info: MessageInfo,
+  cw20: String,
+) -> Result<Response, ContractError> {
+  ensure_unpaused(deps.storage, |paused| paused.auctions)?;
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let mut auction = DUTCH_AUCTIONS
+    .may_load(deps.storage, cw20_address.as_str())?
+    .ok_or(ContractError::NoDutchAuction {})?;
+  auction.resume(auction_paused_seconds(deps.storage, env.block.time)?);
+  let mut vault = load_vault(deps.storage, &cw20_address)?;
+  ensure_active(&vault)?;
 
-  let config = CONFIG.load(deps.storage).unwrap();
-  let (collection_address, token_id) = config.last_nft_fractionalized;
+  let config = CONFIG.load(deps.storage)?;
+  let price = auction.price_at(env.block.time);
+  let paid = must_pay(&info, &config.buyout_denom)?;
+  if paid < price {
+    return Err(ContractError::IncorrectPayment { expected: price });
+  }
 
-  NFT_CW20.save(
+  close_vault(
+    deps.storage,
+    cw20_address.as_str(),
+    &mut vault,
+    VaultStatus::Sold,
+  )?;
+  let fee_msgs = add_proceeds(
     deps.storage,
-    (collection_address.clone(), token_id.clone()),
+    &config,
     &cw20_address,
+    coin(price.u128(), &config.buyout_denom),
+  )?;
+
+  let mut res = Response::new()
+    .add_messages(transfer_nfts_msgs(
+      &vault.collection,
+      info.sender.as_str(),
+      vault.token_ids,
+    )?)
+    .add_messages(fee_msgs);
+  if paid > price {
+    res = res.add_message(BankMsg::Send {
+      to_address: info.sender.to_string(),
+      amount: coins((paid - price).u128(), config.buyout_denom),
+    });
+  }
+
+  Ok(
+    res
+      .add_attribute("action", "buy_now")
+      .add_attribute("cw20_address", cw20_address)
+      .add_attribute("buyer", info.sender)
+      .add_attribute("price", price),
+  )
+}
+
+fn validate_dutch_auction(params: &DutchAuctionParams) -> Result<(), ContractError> {
+  let invalid_decay = match params.decay {
+    PriceDecay::Linear => false,
+    PriceDecay::Exponential { half_life } => half_life == 0,
+  };
+  if params.start_price < params.floor_price || params.duration == 0 || invalid_decay {
+    return Err(ContractError::InvalidDutchAuction {});
+  }
+  Ok(())
+}
+
+fn load_vault(storage: &dyn Storage, cw20_address: &Addr) -> Result<Vault, ContractError> {
+  vaults()
+    .may_load(storage, cw20_address.as_str())?
+    .ok_or(ContractError::NotFractionalized {})
+}
+
+fn ensure_active(vault: &Vault) -> Result<(), ContractError> {
+  match vault.status {
+    VaultStatus::Active => Ok(()),
+    VaultStatus::Auction => Err(ContractError::AuctionInProgress {}),
+    VaultStatus::Shotgun => Err(ContractError::ShotgunInProgress {}),
+    VaultStatus::Sold | VaultStatus::Unfractionalized => Err(ContractError::NotFractionalized {}),
+  }
+}
+
+// Releases the NFT of a vault once it leaves the contract
+fn close_vault(
+  storage: &mut dyn Storage,
+  cw20_address: &str,
+  vault: &mut Vault,
+  status: VaultStatus,
+) -> StdResult<()> {
+  vault.status = status;
+  vaults().save(storage, cw20_address, vault)?;
+  for token_id in &vault.token_ids {
+    NFT_CW20.remove(storage, (vault.collection.clone(), token_id.clone()));
+  }
+  DUTCH_AUCTIONS.remove(storage, cw20_address);
+  CURATORS.remove(storage, cw20_address);
+  SALES.remove(storage, cw20_address);
+  RESERVE_PRICE_OVERRIDES.remove(storage, cw20_address);
+  Ok(())
+}
+
+// Credits buyout proceeds to the share holders, minus the protocol fee
+fn add_proceeds(
+  storage: &mut dyn Storage,
+  config: &Config,
+  cw20_address: &Addr,
+  payment: Coin,
+) -> StdResult<Vec<CosmosMsg>> {
+  let mut msgs = vec![];
+  let mut proceeds = payment.amount;
+  if let Some(fees) = &config.fees {
+    let fee = payment
+      .amount
+      .multiply_ratio(fees.buyout_bps, BPS_DENOMINATOR);
+    if !fee.is_zero() {
+      proceeds -= fee;
+      msgs.push(CosmosMsg::Bank(BankMsg::Send {
+        to_address: fees.recipient.to_string(),
+        amount: coins(fee.u128(), &payment.denom),
+      }));
+    }
+  }
+
+  let mut assets = VAULT_ASSETS
+    .may_load(storage, cw20_address.as_str())?
+    .unwrap_or_default();
+  if !proceeds.is_zero() {
+    assets.add_native(coin(proceeds.u128(), payment.denom));
+  }
+  VAULT_ASSETS.save(storage, cw20_address.as_str(), &assets)?;
+  Ok(msgs)
+}
+
+// Anyone can deposit a cw20, so a token failing its transfer is credited to the recipient to claim
+// later rather than blocking the NFT and the other assets from leaving the vault
+fn asset_msgs(
+  storage: &mut dyn Storage,
+  assets: VaultAssets,
+  recipient: &Addr,
+) -> StdResult<Vec<SubMsg<TokenFactoryMsg>>> {
+  let mut msgs = vec![];
+  if !assets.native.is_empty() {
+    msgs.push(SubMsg::new(BankMsg::Send {
+      to_address: recipient.to_string(),
+      amount: assets.native,
+    }));
+  }
+  for token in assets.cw20 {
+    let id = next_reply_id(storage)?;
+    msgs.push(SubMsg {
+      id,
+      msg: CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: token.address.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+          recipient: recipient.to_string(),
+          amount: token.amount,
+        })?,
+        funds: vec![],
+      }),
+      gas_limit: Some(ASSET_TRANSFER_GAS_LIMIT),
+      reply_on: ReplyOn::Always,
+    });
+    PENDING_TRANSFERS.save(
+      storage,
+      id,
+      &PendingTransfer {
+        recipient: recipient.clone(),
+        token,
+      },
+    )?;
+  }
+  Ok(msgs)
+}
+
+fn load_basket(storage: &dyn Storage, basket_id: u64) -> Result<Basket, ContractError> {
+  BASKETS
+    .may_load(storage, basket_id)?
+    .ok_or(ContractError::BasketNotFound { id: basket_id })
+}
+
+fn transfer_nfts_msgs(
+  collection: &Addr,
+  recipient: &str,
+  token_ids: Vec<String>,
+) -> StdResult<Vec<CosmosMsg>> {
+  token_ids
+    .into_iter()
+    .map(|token_id| {
+      Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: collection.to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::<Empty, Empty>::TransferNft {
+          recipient: recipient.to_string(),
+          token_id,
+        })?,
+        funds: vec![],
+      }))
+    })
+    .collect()
+}
+
+fn mint_msg(cw20_address: &Addr, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+  Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+    contract_addr: cw20_address.to_string(),
+    msg: to_binary(&Cw20ExecuteMsg::Mint {
+      recipient: recipient.to_string(),
+      amount,
+    })?,
+    funds: vec![],
+  }))
+}
+
+fn burn_msg(cw20_address: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+  Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+    contract_addr: cw20_address.to_string(),
+    msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+    funds: vec![],
+  }))
+}
+
+// Reply callback triggered from submessages, dispatched on the pending state stored for its ID
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+  if let Some(transfer) = PENDING_TRANSFERS.may_load(deps.storage, msg.id)? {
+    PENDING_TRANSFERS.remove(deps.storage, msg.id);
+    return handle_asset_transfer_reply(deps, transfer, msg);
+  }
+  if let Some(pending) = PENDING_VAULTS.may_load(deps.storage, msg.id)? {
+    PENDING_VAULTS.remove(deps.storage, msg.id);
+    return handle_instantiate_reply(deps, env, pending, msg);
+  }
+
+  Err(ContractError::UnknownReplyId { id: msg.id })
+}
+
+// The tokens of a failed transfer stay with the contract until the recipient claims them
+fn handle_asset_transfer_reply(
+  deps: DepsMut,
+  transfer: PendingTransfer,
+  msg: Reply,
+) -> Result<Response, ContractError> {
+  let err = match msg.result {
+    SubMsgResult::Ok(_) => return Ok(Response::new()),
+    SubMsgResult::Err(err) => err,
+  };
+  let token = transfer.token;
+  ASSET_CLAIMS.update(
+    deps.storage,
+    (&transfer.recipient, &token.address),
+    |claim| -> StdResult<_> { Ok(claim.unwrap_or_default() + token.amount) },
+  )?;
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "asset_transfer_failed")
+      .add_attribute("recipient", transfer.recipient)
+      .add_attribute("token", token.address)
+      .add_attribute("amount", token.amount)
+      .add_attribute("error", err),
+  )
+}
+
+// Records the vault once its cw20 share token has been instantiated
+pub fn handle_instantiate_reply(
+  deps: DepsMut,
+  env: Env,
+  pending: PendingVault,
+  msg: Reply,
+) -> Result<Response, ContractError> {
+  let res = parse_reply_instantiate_data(msg)?;
+  let cw20_address = deps.api.addr_validate(&res.contract_address)?.to_string();
+  // the config cannot change between instantiating the share token and this reply
+  let cw20_code_id = CONFIG.load(deps.storage)?.cw20_code_id;
+  Ok(
+    register_vault(
+      deps.storage,
+      &env,
+      pending,
+      &cw20_address,
+      Some(cw20_code_id),
+    )?
+    .add_attribute("cw20_address", cw20_address),
+  )
+}
+
+// Records a vault whose shares have been issued, returning the fractionalize response
+fn register_vault(
+  storage: &mut dyn Storage,
+  env: &Env,
+  pending: PendingVault,
+  shares: &str,
+  cw20_code_id: Option<u64>,
+) -> StdResult<Response> {
+  let PendingVault {
+    collection: collection_address,
+    token_ids,
+    creator,
+    dutch_auction,
+    curator_fee_bps,
+    mintable,
+    backend,
+    ..
+  } = pending;
+
+  for token_id in &token_ids {
+    NFT_CW20.save(
+      storage,
+      (collection_address.clone(), token_id.clone()),
+      &shares.to_string(),
+    )?;
+  }
+  vaults().save(
+    storage,
+    shares,
+    &Vault {
+      collection: collection_address.clone(),
+      token_ids: token_ids.clone(),
+      creator: creator.clone(),
+      created_at: env.block.time,
+      status: VaultStatus::Active,
+      mintable: mintable || curator_fee_bps > 0,
+      backend,
+      cw20_code_id,
+    },
   )?;
-  CW20_NFT.save(deps.storage, cw20_address, &(collection_address, token_id))?;
 
-  Ok(Response::new())
+  if curator_fee_bps > 0 {
+    CURATORS.save(
+      storage,
+      shares,
+      &Curator {
+        address: creator.clone(),
+        fee_bps: curator_fee_bps,
+        last_claim: env.block.time,
+      },
+    )?;
+  }
+
+  if let Some(params) = dutch_auction {
+    let paused_seconds = auction_paused_seconds(storage, env.block.time)?;
+    DUTCH_AUCTIONS.save(
+      storage,
+      shares,
+      &DutchAuction {
+        start_price: params.start_price,
+        floor_price: params.floor_price,
+        start_time: env.block.time,
+        duration: params.duration,
+        decay: params.decay,
+        paused_seconds,
+      },
+    )?;
+  }
+
+  Ok(
+    Response::new()
+      .add_attribute("action", "fractionalize")
+      .add_attribute("collection", collection_address)
+      .add_attribute("token_ids", token_ids.join(",")),
+  )
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
   match msg {
+    QueryMsg::Config {} => to_binary(&query_config(deps)?),
+    QueryMsg::Fees {} => to_binary(&query_fees(deps)?),
+    QueryMsg::FeeCredit { address } => to_binary(&query_fee_credit(deps, address)?),
+    QueryMsg::AssetClaims { address } => to_binary(&query_asset_claims(deps, address)?),
     QueryMsg::GetCw20Address { address, token_id } => {
       to_binary(&get_cw20_address(deps, address, token_id)?)
     }
+    QueryMsg::TryGetCw20Address { address, token_id } => {
+      to_binary(&try_get_cw20_address(deps, address, token_id)?)
+    }
+    QueryMsg::Lock { cw20, address } => to_binary(&query_lock(deps, cw20, address)?),
+    QueryMsg::Auction { cw20 } => to_binary(&query_auction(deps, env, cw20)?),
+    QueryMsg::Shotgun { cw20 } => to_binary(&query_shotgun(deps, env, cw20)?),
+    QueryMsg::DutchAuction { cw20 } => to_binary(&query_dutch_auction(deps, env, cw20)?),
+    QueryMsg::ListVaults { start_after, limit } => {
+      to_binary(&list_vaults(deps, start_after, limit)?)
+    }
+    QueryMsg::VaultsByCollection {
+      collection,
+      start_after,
+      limit,
+    } => to_binary(&vaults_by_collection(deps, collection, start_after, limit)?),
+    QueryMsg::VaultByCw20 { cw20 } => to_binary(&vault_by_cw20(deps, cw20)?),
+    QueryMsg::VaultAssets { cw20 } => to_binary(&query_vault_assets(deps, cw20)?),
+    QueryMsg::Basket { basket_id } => to_binary(&query_basket(deps, basket_id)?),
+    QueryMsg::Curator { cw20 } => to_binary(&query_curator(deps, env, cw20)?),
+    QueryMsg::Proposal { proposal_id } => to_binary(&query_proposal(deps, proposal_id)?),
+    QueryMsg::Proposals {
+      cw20,
+      start_after,
+      limit,
+    } => to_binary(&query_proposals(deps, cw20, start_after, limit)?),
+    QueryMsg::Sale { cw20 } => to_binary(&query_sale(deps, cw20)?),
+    QueryMsg::ReservePrice { cw20 } => {
+      to_binary(&reserve_price(deps, &deps.api.addr_validate(&cw20)?)?)
+    }
+    QueryMsg::Paused {} => to_binary(&query_paused(deps)?),
+    QueryMsg::CollectionMode {} => to_binary(&query_collection_mode(deps)?),
+    QueryMsg::Allowlist { start_after, limit } => {
+      to_binary(&query_collections(deps, ALLOWLIST, start_after, limit)?)
+    }
+    QueryMsg::Denylist { start_after, limit } => {
+      to_binary(&query_collections(deps, DENYLIST, start_after, limit)?)
+    }
   }
 }
 
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+  let config = CONFIG.load(deps.storage)?;
+  Ok(ConfigResponse {
+    owner: config.owner.to_string(),
+    cw20_code_id: config.cw20_code_id,
+    buyout_denom: config.buyout_denom,
+    auction_duration: config.auction_duration,
+    shotgun_duration: config.shotgun_duration,
+    max_curator_fee_bps: config.max_curator_fee_bps,
+    governance: config.governance,
+    supply_limits: config.supply_limits,
+    denom_creation_fee: config.denom_creation_fee,
+  })
+}
+
+pub fn query_lock(deps: Deps, cw20: String, address: String) -> StdResult<LockResponse> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let holder = deps.api.addr_validate(&address)?;
+  let lock = LOCKS
+    .may_load(deps.storage, (cw20_address.as_str(), &holder))?
+    .unwrap_or_default();
+  Ok(LockResponse {
+    amount: lock.amount,
+    unlocks_at: lock.unlocks_at,
+  })
+}
+
+pub fn query_paused(deps: Deps) -> StdResult<PauseState> {
+  Ok(PAUSED.may_load(deps.storage)?.unwrap_or_default())
+}
+
+pub fn query_collection_mode(deps: Deps) -> StdResult<CollectionMode> {
+  Ok(
+    COLLECTION_MODE
+      .may_load(deps.storage)?
+      .unwrap_or(CollectionMode::Open),
+  )
+}
+
+fn query_collections(
+  deps: Deps,
+  list: Map<&Addr, Empty>,
+  start_after: Option<String>,
+  limit: Option<u32>,
+) -> StdResult<CollectionsResponse> {
+  let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+  let start_after = start_after
+    .map(|collection| deps.api.addr_validate(&collection))
+    .transpose()?;
+
+  let collections = list
+    .keys(
+      deps.storage,
+      start_after.as_ref().map(Bound::exclusive),
+      None,
+      Order::Ascending,
+    )
+    .take(limit)
+    .map(|item| item.map(String::from))
+    .collect::<StdResult<_>>()?;
+
+  Ok(CollectionsResponse { collections })
+}
+
+pub fn query_fees(deps: Deps) -> StdResult<Option<FeeConfig>> {
+  let config = CONFIG.load(deps.storage)?;
+  Ok(config.fees.map(|fees| FeeConfig {
+    recipient: fees.recipient.into_string(),
+    fractionalize_bps: fees.fractionalize_bps,
+    flat_fee: fees.flat_fee,
+    buyout_bps: fees.buyout_bps,
+  }))
+}
+
+pub fn query_fee_credit(deps: Deps, address: String) -> StdResult<FeeCreditResponse> {
+  let payer = deps.api.addr_validate(&address)?;
+  Ok(FeeCreditResponse {
+    credit: fee_credit(deps.storage, &payer)?,
+  })
+}
+
+pub fn query_asset_claims(deps: Deps, address: String) -> StdResult<AssetClaimsResponse> {
+  let recipient = deps.api.addr_validate(&address)?;
+  let claims = ASSET_CLAIMS
+    .prefix(&recipient)
+    .range(deps.storage, None, None, Order::Ascending)
+    .map(|item| {
+      item.map(|(token, amount)| Cw20Coin {
+        address: token.into_string(),
+        amount,
+      })
+    })
+    .collect::<StdResult<_>>()?;
+  Ok(AssetClaimsResponse { claims })
+}
+
+pub fn query_auction(deps: Deps, env: Env, cw20: String) -> StdResult<Option<AuctionResponse>> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let paused_seconds = auction_paused_seconds(deps.storage, env.block.time)?;
+  let auction = AUCTIONS.may_load(deps.storage, cw20_address.as_str())?;
+  Ok(auction.map(|mut auction| {
+    auction.resume(paused_seconds);
+    AuctionResponse {
+      bidder: auction.bidder.to_string(),
+      amount: auction.amount,
+      denom: auction.denom,
+      end_time: auction.end_time,
+    }
+  }))
+}
+
+pub fn query_shotgun(deps: Deps, env: Env, cw20: String) -> StdResult<Option<ShotgunResponse>> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let paused_seconds = auction_paused_seconds(deps.storage, env.block.time)?;
+  let shotgun = SHOTGUNS.may_load(deps.storage, cw20_address.as_str())?;
+  Ok(shotgun.map(|mut shotgun| {
+    shotgun.resume(paused_seconds);
+    ShotgunResponse {
+      initiator: shotgun.initiator.to_string(),
+      price_per_share: shotgun.price_per_share,
+      shares: shotgun.shares,
+      escrow: shotgun.escrow,
+      denom: shotgun.denom,
+      deadline: shotgun.deadline,
+    }
+  }))
+}
+
+pub fn query_dutch_auction(
+  deps: Deps,
+  env: Env,
+  cw20: String,
+) -> StdResult<Option<DutchAuctionResponse>> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let paused_seconds = auction_paused_seconds(deps.storage, env.block.time)?;
+  let auction = DUTCH_AUCTIONS.may_load(deps.storage, cw20_address.as_str())?;
+  Ok(auction.map(|mut auction| {
+    auction.resume(paused_seconds);
+    DutchAuctionResponse {
+      current_price: auction.price_at(env.block.time),
+      start_price: auction.start_price,
+      floor_price: auction.floor_price,
+      start_time: auction.start_time,
+      end_time: auction.start_time.plus_seconds(auction.duration),
+      decay: auction.decay,
+    }
+  }))
+}
+
 pub fn get_cw20_address(
   deps: Deps,
   address: String,
   token_id: String,
 ) -> StdResult<GetCw20AddressResponse> {
-  let nft_address = deps.api.addr_validate(&address).unwrap();
-  let cw20_address = NFT_CW20
-    .load(deps.storage, (nft_address, token_id))
-    .unwrap();
+  let res = try_get_cw20_address(deps, address.clone(), token_id.clone())?;
+  let cw20_address = res.address.ok_or_else(|| {
+    StdError::generic_err(format!(
+      "Token {} of collection {} is not fractionalized",
+      token_id, address
+    ))
+  })?;
 
   Ok(GetCw20AddressResponse {
     address: cw20_address,
   })
 }
+
+pub fn try_get_cw20_address(
+  deps: Deps,
+  address: String,
+  token_id: String,
+) -> StdResult<TryGetCw20AddressResponse> {
+  let nft_address = deps.api.addr_validate(&address)?;
+  let cw20_address = NFT_CW20.may_load(deps.storage, (nft_address, token_id))?;
+
+  Ok(TryGetCw20AddressResponse {
+    is_fractionalized: cw20_address.is_some(),
+    address: cw20_address,
+  })
+}
+
+pub fn list_vaults(
+  deps: Deps,
+  start_after: Option<String>,
+  limit: Option<u32>,
+) -> StdResult<VaultsResponse> {
+  let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+  let start = start_after.as_deref().map(Bound::exclusive);
+
+  let vaults = vaults()
+    .range(deps.storage, start, None, Order::Ascending)
+    .take(limit)
+    .map(|item| item.map(|(cw20, vault)| vault_info(cw20, vault)))
+    .collect::<StdResult<_>>()?;
+
+  Ok(VaultsResponse { vaults })
+}
+
+pub fn vaults_by_collection(
+  deps: Deps,
+  collection: String,
+  start_after: Option<String>,
+  limit: Option<u32>,
+) -> StdResult<VaultsResponse> {
+  let collection = deps.api.addr_validate(&collection)?;
+  let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+  let start = start_after.map(Bound::exclusive);
+
+  let vaults = vaults()
+    .idx
+    .collection
+    .prefix(collection)
+    .range(deps.storage, start, None, Order::Ascending)
+    .take(limit)
+    .map(|item| item.map(|(cw20, vault)| vault_info(cw20, vault)))
+    .collect::<StdResult<_>>()?;
+
+  Ok(VaultsResponse { vaults })
+}
+
+pub fn vault_by_cw20(deps: Deps, cw20: String) -> StdResult<VaultInfo> {
+  // tokenfactory vaults are keyed by their denom instead of a share token address
+  let shares = if SHARE_DENOMS.has(deps.storage, &cw20) {
+    cw20
+  } else {
+    deps.api.addr_validate(&cw20)?.into_string()
+  };
+  let vault = vaults().load(deps.storage, &shares)?;
+  Ok(vault_info(shares, vault))
+}
+
+pub fn query_curator(deps: Deps, env: Env, cw20: String) -> StdResult<Option<CuratorResponse>> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let curator = match CURATORS.may_load(deps.storage, cw20_address.as_str())? {
+    Some(curator) => curator,
+    None => return Ok(None),
+  };
+  let cw20_info: cw20::TokenInfoResponse = deps
+    .querier
+    .query_wasm_smart(cw20_address, &cw20_base::msg::QueryMsg::TokenInfo {})?;
+
+  Ok(Some(CuratorResponse {
+    claimable: curator.accrued(cw20_info.total_supply, env.block.time),
+    curator: curator.address.into_string(),
+    fee_bps: curator.fee_bps,
+    last_claim: curator.last_claim,
+  }))
+}
+
+pub fn query_proposal(deps: Deps, proposal_id: u64) -> StdResult<ProposalResponse> {
+  let proposal = proposals().load(deps.storage, proposal_id)?;
+  Ok(proposal_response(proposal_id, proposal))
+}
+
+pub fn query_proposals(
+  deps: Deps,
+  cw20: String,
+  start_after: Option<u64>,
+  limit: Option<u32>,
+) -> StdResult<ProposalsResponse> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+  let start = start_after.map(Bound::exclusive);
+
+  let proposals = proposals()
+    .idx
+    .cw20
+    .prefix(cw20_address)
+    .range(deps.storage, start, None, Order::Ascending)
+    .take(limit)
+    .map(|item| item.map(|(id, proposal)| proposal_response(id, proposal)))
+    .collect::<StdResult<_>>()?;
+
+  Ok(ProposalsResponse { proposals })
+}
+
+fn proposal_response(id: u64, proposal: Proposal) -> ProposalResponse {
+  ProposalResponse {
+    id,
+    cw20: proposal.cw20.into_string(),
+    proposer: proposal.proposer.into_string(),
+    action: proposal.action,
+    yes_votes: proposal.yes_votes,
+    no_votes: proposal.no_votes,
+    abstain_votes: proposal.abstain_votes,
+    end_time: proposal.end_time,
+    status: proposal.status,
+  }
+}
+
+pub fn query_sale(deps: Deps, cw20: String) -> StdResult<Option<SaleResponse>> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let sale = SALES.may_load(deps.storage, cw20_address.as_str())?;
+  Ok(sale.map(|sale| SaleResponse {
+    buyer: sale.buyer.into_string(),
+    price: sale.price,
+  }))
+}
+
+pub fn query_basket(deps: Deps, basket_id: u64) -> StdResult<Option<BasketResponse>> {
+  let basket = BASKETS.may_load(deps.storage, basket_id)?;
+  Ok(basket.map(|basket| BasketResponse {
+    creator: basket.creator.into_string(),
+    collection: basket.collection.into_string(),
+    token_ids: basket.token_ids,
+  }))
+}
+
+pub fn query_vault_assets(deps: Deps, cw20: String) -> StdResult<VaultAssetsResponse> {
+  let cw20_address = deps.api.addr_validate(&cw20)?;
+  let assets = VAULT_ASSETS
+    .may_load(deps.storage, cw20_address.as_str())?
+    .unwrap_or_default();
+  Ok(VaultAssetsResponse {
+    native: assets.native,
+    cw20: assets
+      .cw20
+      .into_iter()
+      .map(|token| Cw20Coin {
+        address: token.address.into_string(),
+        amount: token.amount,
+      })
+      .collect(),
+  })
+}
+
+fn vault_info(cw20: String, vault: Vault) -> VaultInfo {
+  VaultInfo {
+    collection: vault.collection.into_string(),
+    token_ids: vault.token_ids,
+    cw20,
+    creator: vault.creator.into_string(),
+    created_at: vault.created_at,
+    status: vault.status,
+    mintable: vault.mintable,
+    backend: vault.backend,
+  }
+}
@@ -1,4 +1,5 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, OverflowError, StdError, Timestamp, Uint128};
+use cw_utils::{ParseReplyError, PaymentError};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -6,6 +7,15 @@ pub enum ContractError {
   #[error("{0}")]
   Std(#[from] StdError),
 
+  #[error("{0}")]
+  ParseReply(#[from] ParseReplyError),
+
+  #[error("{0}")]
+  Payment(#[from] PaymentError),
+
+  #[error("{0}")]
+  Overflow(#[from] OverflowError),
+
   #[error("Unauthorized")]
   Unauthorized {},
 
@@ -15,12 +25,195 @@ pub enum ContractError {
   #[error("Already fractionalized")]
   Exists {},
 
+  #[error("NFT {token_id} has not been transferred to the contract")]
+  NftNotHeld { token_id: String },
+
+  #[error("Share token was not instantiated by this contract or has been tampered with")]
+  InvalidShareToken {},
+
   #[error("Insufficient funds")]
   InsufficientFunds {},
 
   #[error("Not fractionalized")]
   NotFractionalized {},
 
+  #[error("No voting power")]
+  NoVotingPower {},
+
+  #[error("No reserve price has been voted")]
+  NoReservePrice {},
+
+  #[error("Reserve price needs votes from {min_bps} bps of the shares")]
+  ReserveQuorumNotReached { min_bps: u64 },
+
+  #[error("Bid too low, minimum is {min_bid}")]
+  BidTooLow { min_bid: Uint128 },
+
+  #[error("Buyout auction in progress")]
+  AuctionInProgress {},
+
+  #[error("No buyout auction in progress")]
+  NoAuction {},
+
+  #[error("Buyout auction has ended")]
+  AuctionEnded {},
+
+  #[error("Buyout auction has not ended yet")]
+  AuctionNotEnded {},
+
+  #[error("Shotgun offer in progress")]
+  ShotgunInProgress {},
+
+  #[error("No shotgun offer in progress")]
+  NoShotgun {},
+
+  #[error("Shotgun offer has expired")]
+  ShotgunExpired {},
+
+  #[error("Shotgun offer can still be countered")]
+  ShotgunNotExpired {},
+
+  #[error("No shares held")]
+  NoShares {},
+
+  #[error("Sender already holds every share")]
+  OwnsAllShares {},
+
+  #[error("Incorrect payment, expected {expected}")]
+  IncorrectPayment { expected: Uint128 },
+
+  #[error("Invalid Dutch auction parameters")]
+  InvalidDutchAuction {},
+
+  #[error("No Dutch auction for this vault")]
+  NoDutchAuction {},
+
+  #[error("Vault holds nothing to redeem")]
+  NothingToRedeem {},
+
+  #[error("Redeeming the whole supply would strand the NFT, unfractionalize instead")]
+  RedeemWholeSupply {},
+
+  #[error("Share tokens cannot be deposited into their own vault")]
+  InvalidDeposit {},
+
+  #[error("A vault holds at most {max} different cw20 tokens")]
+  TooManyDepositTokens { max: usize },
+
+  #[error("No failed payout of this token to claim")]
+  NoAssetClaim {},
+
+  #[error("Fee basis points cannot exceed 10000")]
+  InvalidFee {},
+
+  #[error("Flat fee of {fee} has not been paid")]
+  FeeNotPaid { fee: Coin },
+
+  #[error("No prepaid fees to withdraw")]
+  NoFeeCredit {},
+
+  #[error("Curator fee cannot exceed {max} basis points")]
+  CuratorFeeTooHigh { max: u64 },
+
+  #[error("Vault has no curator fee")]
+  NoCurator {},
+
+  #[error("Name is not in the expected format (3-50 UTF-8 bytes)")]
+  InvalidName {},
+
+  #[error("Ticker symbol is not in expected format [a-zA-Z\\-]{{3,12}}")]
+  InvalidSymbol {},
+
+  #[error("Decimals must not exceed 18")]
+  InvalidDecimals {},
+
+  #[error("Label must not be empty")]
+  InvalidLabel {},
+
+  #[error("No initial share owners")]
+  NoOwners {},
+
+  #[error("Invalid owner address {address}")]
+  InvalidOwner { address: String },
+
+  #[error("Duplicate owner {address}")]
+  DuplicateOwner { address: String },
+
+  #[error("Initial share supply is zero")]
+  ZeroSupply {},
+
+  #[error("Initial share supply must be at least {min}")]
+  SupplyTooLow { min: Uint128 },
+
+  #[error("Initial share supply must be at most {max}")]
+  SupplyTooHigh { max: Uint128 },
+
+  #[error("Minimum supply exceeds maximum supply")]
+  InvalidSupplyLimits {},
+
+  #[error("Option not supported by the share backend")]
+  UnsupportedByBackend {},
+
+  #[error("Invalid governance parameters")]
+  InvalidGovernance {},
+
+  #[error("Proposal {id} not found")]
+  ProposalNotFound { id: u64 },
+
+  #[error("Voting has ended")]
+  VotingEnded {},
+
+  #[error("Voting has not ended yet")]
+  VotingNotEnded {},
+
+  #[error("Already voted")]
+  AlreadyVoted {},
+
+  #[error("Proposal is closed")]
+  ProposalClosed {},
+
+  #[error("Shares are locked until {unlocks_at}")]
+  SharesLocked { unlocks_at: Timestamp },
+
+  #[error("NFT actions can only revoke approvals of the vault's NFTs")]
+  ForbiddenNftAction {},
+
+  #[error("Vault shares are not mintable")]
+  NotMintable {},
+
+  #[error("No sale has been approved")]
+  NoSale {},
+
+  #[error("Basket {id} not found")]
+  BasketNotFound { id: u64 },
+
+  #[error("Basket holds no NFTs")]
+  EmptyBasket {},
+
+  #[error("Basket only accepts NFTs from {collection}")]
+  WrongCollection { collection: String },
+
+  #[error("Cannot migrate from contract {contract}")]
+  WrongContract { contract: String },
+
+  #[error("Invalid contract version {version}")]
+  InvalidVersion { version: String },
+
+  #[error("Cannot downgrade from {stored} to {current}")]
+  CannotDowngrade { stored: String, current: String },
+
+  #[error("Migrating from {from} requires a config with an owner")]
+  MissingMigrationConfig { from: String },
+
+  #[error("Collection {collection} cannot be fractionalized")]
+  CollectionNotAllowed { collection: String },
+
+  #[error("Paused")]
+  Paused {},
+
+  #[error("Unknown reply ID: {id}")]
+  UnknownReplyId { id: u64 },
+
   #[error("Custom Error val: {val:?}")]
   CustomError { val: String },
   // Add any other custom errors you like here.
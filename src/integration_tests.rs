@@ -1,20 +1,148 @@
+use std::fmt::Debug;
+
 use cosmwasm_std::{
-  testing::{mock_dependencies, mock_info, MockApi, MockQuerier},
-  to_binary, Addr, Api, Empty, MemoryStorage, OwnedDeps, Uint128,
+  coin, coins, from_binary,
+  testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier},
+  to_binary, Addr, Api, BankMsg, Binary, BlockInfo, ContractInfoResponse, ContractResult,
+  CosmosMsg, CustomQuery, Deps, DepsMut, Empty, Env, MemoryStorage, MessageInfo, OwnedDeps,
+  Querier, Reply, Response, StdError, StdResult, Storage, SubMsgResponse, SubMsgResult,
+  SystemResult, Uint128, WasmMsg, WasmQuery,
 };
 
-use cw20::Cw20Coin;
-use cw20::Cw20QueryMsg;
+use cw20::{Cw20Coin, Cw20ReceiveMsg};
+use cw20::{Cw20QueryMsg, LogoInfo, MarketingInfoResponse, MinterResponse, TokenInfoResponse};
 use cw20_base::msg::ExecuteMsg as Cw20ExecuteMsg;
-use cw721::Cw721QueryMsg;
+use cw721::{Cw721QueryMsg, Cw721ReceiveMsg};
 use cw721::{NumTokensResponse, OwnerOfResponse};
 use cw721_base::msg::ExecuteMsg as Cw721ExecuteMsg;
 use cw721_base::{Extension, InstantiateMsg as Cw721InstantiateMsg, MintMsg};
-use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+use cw_multi_test::{
+  AppResponse, BankKeeper, BankSudo, BasicAppBuilder, Contract, ContractWrapper, CosmosRouter,
+  Executor, Module, SudoMsg, WasmKeeper,
+};
+use cw_utils::ParseReplyError;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 
-use crate::contract::{execute, instantiate, query, reply};
+use crate::contract::{execute, instantiate, migrate, query, reply};
 use crate::error::ContractError;
-use crate::msg::{GetCw20AddressResponse, InstantiateMsg, QueryMsg, ReceiveMsg};
+use crate::msg::{
+  AssetClaimsResponse, BasketResponse, CollectionMode, CollectionsResponse, ConfigResponse,
+  CuratorResponse, DutchAuctionParams, DutchAuctionResponse, ExecuteMsg, FeeConfig,
+  FeeCreditResponse, FinalizeBasketMsg, GetCw20AddressResponse, GovernanceConfig, InstantiateMsg,
+  LockResponse, MigrateMsg, PauseState, PriceDecay, ProposalAction, ProposalResponse,
+  ProposalStatus, ProposalsResponse, QueryMsg, ReceiveMsg, ReservePriceResponse, SaleResponse,
+  ShareBackend, ShareTokenMarketing, ShareTokenParams, ShotgunResponse, SupplyLimits,
+  TokenFactoryMsg, TryGetCw20AddressResponse, UpdateConfigMsg, VaultAssetsResponse, VaultInfo,
+  VaultStatus, VaultsResponse, VoteOption,
+};
+use crate::state::{
+  vaults, PendingVault, Vault, LEGACY_CW20_NFT, NFT_CW20, PENDING_VAULTS, SECONDS_PER_YEAR,
+};
+
+type App = cw_multi_test::App<
+  BankKeeper,
+  MockApi,
+  MemoryStorage,
+  TokenFactoryModule,
+  WasmKeeper<TokenFactoryMsg, Empty>,
+>;
+
+// Mints and burns tokenfactory denoms through the bank module, on behalf of their creator
+pub struct TokenFactoryModule {}
+
+impl Module for TokenFactoryModule {
+  type ExecT = TokenFactoryMsg;
+  type QueryT = Empty;
+  type SudoT = Empty;
+
+  fn execute<ExecC, QueryC>(
+    &self,
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    block: &BlockInfo,
+    sender: Addr,
+    msg: TokenFactoryMsg,
+  ) -> anyhow::Result<AppResponse>
+  where
+    ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+    QueryC: CustomQuery + DeserializeOwned + 'static,
+  {
+    let is_admin = |denom: &str| denom.starts_with(&format!("factory/{}/", sender));
+    match msg {
+      TokenFactoryMsg::CreateDenom { subdenom } => {
+        if subdenom.len() > 44 {
+          anyhow::bail!("subdenom {} is too long", subdenom);
+        }
+        Ok(AppResponse::default())
+      }
+      TokenFactoryMsg::MintTokens {
+        denom,
+        amount,
+        mint_to_address,
+      } => {
+        if !is_admin(&denom) {
+          anyhow::bail!("{} cannot mint {}", sender, denom);
+        }
+        router.sudo(
+          api,
+          storage,
+          block,
+          SudoMsg::Bank(BankSudo::Mint {
+            to_address: mint_to_address,
+            amount: coins(amount.u128(), denom),
+          }),
+        )
+      }
+      TokenFactoryMsg::BurnTokens {
+        denom,
+        amount,
+        burn_from_address,
+      } => {
+        if !is_admin(&denom) {
+          anyhow::bail!("{} cannot burn {}", sender, denom);
+        }
+        router.execute(
+          api,
+          storage,
+          block,
+          Addr::unchecked(burn_from_address),
+          BankMsg::Burn {
+            amount: coins(amount.u128(), denom),
+          }
+          .into(),
+        )
+      }
+    }
+  }
+
+  fn sudo<ExecC, QueryC>(
+    &self,
+    _api: &dyn Api,
+    _storage: &mut dyn Storage,
+    _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+    _block: &BlockInfo,
+    _msg: Empty,
+  ) -> anyhow::Result<AppResponse>
+  where
+    ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+    QueryC: CustomQuery + DeserializeOwned + 'static,
+  {
+    anyhow::bail!("tokenfactory sudo is not supported")
+  }
+
+  fn query(
+    &self,
+    _api: &dyn Api,
+    _storage: &dyn Storage,
+    _querier: &dyn Querier,
+    _block: &BlockInfo,
+    _request: Empty,
+  ) -> anyhow::Result<Binary> {
+    anyhow::bail!("tokenfactory queries are not supported")
+  }
+}
 
 pub fn nft_owner_of(router: &mut App, collection: String, token_id: String) -> String {
   let msg = Cw721QueryMsg::OwnerOf {
@@ -25,6 +153,34 @@ pub fn nft_owner_of(router: &mut App, collection: String, token_id: String) -> S
   res.owner
 }
 
+const DENOM: &str = "uusd";
+const AUCTION_DURATION: u64 = 86400;
+const SHOTGUN_DURATION: u64 = 3600;
+const MAX_CURATOR_FEE_BPS: u64 = 1000;
+const VOTING_PERIOD: u64 = 3600;
+
+fn governance() -> GovernanceConfig {
+  GovernanceConfig {
+    voting_period: VOTING_PERIOD,
+    quorum_bps: 2000,
+    threshold_bps: 5000,
+    reserve_quorum_bps: 5000,
+  }
+}
+
+pub fn fund(router: &mut App, address: Addr, amount: u128) {
+  router
+    .sudo(SudoMsg::Bank(BankSudo::Mint {
+      to_address: address.to_string(),
+      amount: coins(amount, DENOM),
+    }))
+    .unwrap();
+}
+
+pub fn native_balance(router: &mut App, address: Addr) -> Uint128 {
+  router.wrap().query_balance(address, DENOM).unwrap().amount
+}
+
 pub fn token_balance(router: &mut App, token: String, address: String) -> Uint128 {
   let msg = Cw20QueryMsg::Balance { address };
   let res: cw20::BalanceResponse = router.wrap().query_wasm_smart(token, &msg).unwrap();
@@ -93,6 +249,48 @@ pub fn get_fractional_address(
   cw20_address.address
 }
 
+pub fn fractionalize_msg(
+  fractionalizer_address: Addr,
+  collection: Addr,
+  token_id: String,
+  owners: Vec<Cw20Coin>,
+) -> CosmosMsg<TokenFactoryMsg> {
+  send_nft_msg(
+    fractionalizer_address,
+    collection,
+    token_id,
+    &ReceiveMsg::Fractionalize {
+      owners,
+      name: Some("name".to_string()),
+      symbol: Some("symbol".to_string()),
+      dutch_auction: None,
+      curator_fee_bps: None,
+      mintable: None,
+      mint_cap: None,
+      share_token: None,
+      backend: None,
+    },
+  )
+}
+
+pub fn send_nft_msg(
+  fractionalizer_address: Addr,
+  collection: Addr,
+  token_id: String,
+  msg: &ReceiveMsg,
+) -> CosmosMsg<TokenFactoryMsg> {
+  CosmosMsg::Wasm(WasmMsg::Execute {
+    contract_addr: collection.to_string(),
+    msg: to_binary(&Cw721ExecuteMsg::<Empty, Empty>::SendNft {
+      contract: fractionalizer_address.to_string(),
+      token_id,
+      msg: to_binary(msg).unwrap(),
+    })
+    .unwrap(),
+    funds: vec![],
+  })
+}
+
 pub fn fractionalize(
   router: &mut App,
   sender: Addr,
@@ -101,22 +299,46 @@ pub fn fractionalize(
   token_id: String,
   owners: Vec<Cw20Coin>,
 ) {
-  let msg = Cw721ExecuteMsg::<Empty, Empty>::SendNft {
+  let msg = fractionalize_msg(fractionalizer_address, collection, token_id, owners);
+  router.execute(sender, msg).unwrap();
+}
+
+pub fn unfractionalize(
+  router: &mut App,
+  sender: Addr,
+  fractionalizer_address: Addr,
+  cw20_address: Addr,
+  amount: Uint128,
+) -> Result<AppResponse, anyhow::Error> {
+  let msg = Cw20ExecuteMsg::Send {
     contract: fractionalizer_address.to_string(),
-    token_id,
-    msg: to_binary(&ReceiveMsg::Fractionalize {
-      owners,
-      name: "name".to_string(),
-      symbol: "symbol".to_string(),
+    amount,
+    msg: to_binary(&ReceiveMsg::Unfractionalize {
+      recipient: sender.to_string(),
     })
     .unwrap(),
   };
-  router
-    .execute_contract(sender, collection, &msg, &[])
-    .unwrap();
+
+  router.execute_contract(sender, cw20_address, &msg, &[])
 }
 
-pub fn unfractionalize(
+pub fn lock_shares(
+  router: &mut App,
+  sender: Addr,
+  fractionalizer_address: Addr,
+  cw20_address: Addr,
+  amount: u128,
+) -> Result<AppResponse, anyhow::Error> {
+  let msg = Cw20ExecuteMsg::Send {
+    contract: fractionalizer_address.to_string(),
+    amount: Uint128::from(amount),
+    msg: to_binary(&ReceiveMsg::Lock {}).unwrap(),
+  };
+
+  router.execute_contract(sender, cw20_address, &msg, &[])
+}
+
+pub fn redeem(
   router: &mut App,
   sender: Addr,
   fractionalizer_address: Addr,
@@ -126,7 +348,7 @@ pub fn unfractionalize(
   let msg = Cw20ExecuteMsg::Send {
     contract: fractionalizer_address.to_string(),
     amount,
-    msg: to_binary(&ReceiveMsg::Unfractionalize {
+    msg: to_binary(&ReceiveMsg::Redeem {
       recipient: sender.to_string(),
     })
     .unwrap(),
@@ -135,8 +357,58 @@ pub fn unfractionalize(
   router.execute_contract(sender, cw20_address, &msg, &[])
 }
 
-pub fn contract_cw721() -> Box<dyn Contract<Empty>> {
-  let contract = ContractWrapper::new(
+pub fn set_reserve_price(
+  router: &mut App,
+  sender: Addr,
+  fractionalizer_address: Addr,
+  cw20: String,
+  price: u128,
+) -> Result<AppResponse, anyhow::Error> {
+  let msg = ExecuteMsg::SetReservePrice {
+    cw20,
+    price: Uint128::from(price),
+  };
+  router.execute_contract(sender, fractionalizer_address, &msg, &[])
+}
+
+pub fn bid(
+  router: &mut App,
+  sender: Addr,
+  fractionalizer_address: Addr,
+  cw20: String,
+  amount: u128,
+) -> Result<AppResponse, anyhow::Error> {
+  let msg = ExecuteMsg::Bid { cw20 };
+  router.execute_contract(sender, fractionalizer_address, &msg, &coins(amount, DENOM))
+}
+
+pub fn start_shotgun(
+  router: &mut App,
+  sender: Addr,
+  fractionalizer_address: Addr,
+  cw20_address: Addr,
+  shares: u128,
+  price_per_share: u128,
+  funds: u128,
+) -> Result<AppResponse, anyhow::Error> {
+  let msg = Cw20ExecuteMsg::IncreaseAllowance {
+    spender: fractionalizer_address.to_string(),
+    amount: Uint128::from(shares),
+    expires: None,
+  };
+  router
+    .execute_contract(sender.clone(), cw20_address.clone(), &msg, &[])
+    .unwrap();
+
+  let msg = ExecuteMsg::StartShotgun {
+    cw20: cw20_address.to_string(),
+    price_per_share: Uint128::from(price_per_share),
+  };
+  router.execute_contract(sender, fractionalizer_address, &msg, &coins(funds, DENOM))
+}
+
+pub fn contract_cw721() -> Box<dyn Contract<TokenFactoryMsg>> {
+  let contract = ContractWrapper::new_with_empty(
     cw721_base::entry::execute,
     cw721_base::entry::instantiate,
     cw721_base::entry::query,
@@ -144,8 +416,60 @@ pub fn contract_cw721() -> Box<dyn Contract<Empty>> {
   Box::new(contract)
 }
 
-pub fn contract_cw20() -> Box<dyn Contract<Empty>> {
-  let contract = ContractWrapper::new(
+// Forwards `SendNft` to the receiver while the NFT stays with its real owner, which `OwnerOf`
+// honestly reports, like a buggy collection would
+fn fake_cw721_execute(
+  _deps: DepsMut,
+  _env: Env,
+  info: MessageInfo,
+  msg: Cw721ExecuteMsg<Empty, Empty>,
+) -> StdResult<Response> {
+  match msg {
+    Cw721ExecuteMsg::SendNft {
+      contract,
+      token_id,
+      msg,
+    } => Ok(
+      Response::new().add_message(
+        Cw721ReceiveMsg {
+          sender: info.sender.to_string(),
+          token_id,
+          msg,
+        }
+        .into_cosmos_msg(contract)?,
+      ),
+    ),
+    _ => Err(StdError::generic_err("unsupported")),
+  }
+}
+
+fn fake_cw721_instantiate(
+  _deps: DepsMut,
+  _env: Env,
+  _info: MessageInfo,
+  _msg: Empty,
+) -> StdResult<Response> {
+  Ok(Response::new())
+}
+
+fn fake_cw721_query(_deps: Deps, _env: Env, msg: Cw721QueryMsg) -> StdResult<Binary> {
+  match msg {
+    Cw721QueryMsg::OwnerOf { .. } => to_binary(&OwnerOfResponse {
+      owner: "owner".to_string(),
+      approvals: vec![],
+    }),
+    _ => Err(StdError::generic_err("unsupported")),
+  }
+}
+
+pub fn contract_fake_cw721() -> Box<dyn Contract<TokenFactoryMsg>> {
+  let contract =
+    ContractWrapper::new_with_empty(fake_cw721_execute, fake_cw721_instantiate, fake_cw721_query);
+  Box::new(contract)
+}
+
+pub fn contract_cw20() -> Box<dyn Contract<TokenFactoryMsg>> {
+  let contract = ContractWrapper::new_with_empty(
     cw20_base::contract::execute,
     cw20_base::contract::instantiate,
     cw20_base::contract::query,
@@ -153,7 +477,7 @@ pub fn contract_cw20() -> Box<dyn Contract<Empty>> {
   Box::new(contract)
 }
 
-pub fn contract_fractionalizer() -> Box<dyn Contract<Empty>> {
+pub fn contract_fractionalizer() -> Box<dyn Contract<TokenFactoryMsg>> {
   let contract = ContractWrapper::new(execute, instantiate, query).with_reply(reply);
   Box::new(contract)
 }
@@ -162,6 +486,8 @@ struct World {
   deps: OwnedDeps<MemoryStorage, MockApi, MockQuerier>,
 
   deployer_address: Addr,
+  cw20_code_id: u64,
+  cw721_code_id: u64,
   user_one: Addr,
   user_two: Addr,
   nft_address: Addr,
@@ -177,10 +503,10 @@ fn setup(router: &mut App) -> World {
   let user_two = mock_info("user_two", &[]);
 
   // CW20
-  router.store_code(contract_cw20());
+  let cw20_code_id = router.store_code(contract_cw20());
 
   // NFT
-  let contract_code_id = router.store_code(contract_cw721());
+  let cw721_code_id = router.store_code(contract_cw721());
   let msg = Cw721InstantiateMsg {
     minter: deployer.sender.clone().into_string(),
     name: "Mock NFT".to_string(),
@@ -188,7 +514,7 @@ fn setup(router: &mut App) -> World {
   };
   let nft_address = router
     .instantiate_contract(
-      contract_code_id,
+      cw721_code_id,
       deployer.clone().sender,
       &msg,
       &[],
@@ -199,7 +525,18 @@ fn setup(router: &mut App) -> World {
 
   // Fractionalizer
   let contract_code_id = router.store_code(contract_fractionalizer());
-  let msg = InstantiateMsg {};
+  let msg = InstantiateMsg {
+    owner: None,
+    cw20_code_id,
+    buyout_denom: DENOM.to_string(),
+    auction_duration: AUCTION_DURATION,
+    shotgun_duration: SHOTGUN_DURATION,
+    fees: None,
+    max_curator_fee_bps: MAX_CURATOR_FEE_BPS,
+    governance: governance(),
+    supply_limits: None,
+    denom_creation_fee: None,
+  };
   let fractionalizer_address = router
     .instantiate_contract(
       contract_code_id,
@@ -211,12 +548,14 @@ fn setup(router: &mut App) -> World {
     )
     .unwrap();
 
-  let nft_address = deps.api.addr_validate(&nft_address.to_string()).unwrap();
+  let nft_address = deps.api.addr_validate(nft_address.as_ref()).unwrap();
 
   World {
     deps,
 
     deployer_address: deployer.sender,
+    cw20_code_id,
+    cw721_code_id,
     fractionalizer_address,
     nft_address,
     user_one: user_one.sender,
@@ -225,7 +564,9 @@ fn setup(router: &mut App) -> World {
 }
 
 fn mock_app() -> App {
-  App::new(|_a, _b, _c| {})
+  BasicAppBuilder::<TokenFactoryMsg, Empty>::new_custom()
+    .with_custom(TokenFactoryModule {})
+    .build(|_a, _b, _c| {})
 }
 
 #[test]
@@ -242,6 +583,84 @@ fn test_setup() {
   assert_eq!(0, res.count);
 }
 
+#[test]
+fn test_update_config() {
+  let router = &mut mock_app();
+  let w = setup(router);
+
+  let config: ConfigResponse = router
+    .wrap()
+    .query_wasm_smart(w.fractionalizer_address.clone(), &QueryMsg::Config {})
+    .unwrap();
+  assert_eq!(
+    config,
+    ConfigResponse {
+      owner: w.deployer_address.to_string(),
+      cw20_code_id: w.cw20_code_id,
+      buyout_denom: DENOM.to_string(),
+      auction_duration: AUCTION_DURATION,
+      shotgun_duration: SHOTGUN_DURATION,
+      max_curator_fee_bps: MAX_CURATOR_FEE_BPS,
+      governance: governance(),
+      supply_limits: SupplyLimits::default(),
+      denom_creation_fee: None,
+    }
+  );
+
+  let msg = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+    owner: Some(w.user_one.to_string()),
+    cw20_code_id: Some(42),
+    buyout_denom: None,
+    auction_duration: Some(60),
+    shotgun_duration: None,
+    fees: None,
+    max_curator_fee_bps: None,
+    governance: None,
+    supply_limits: None,
+    denom_creation_fee: None,
+  });
+  let err = router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::Unauthorized {}
+  );
+
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &[],
+    )
+    .unwrap();
+
+  let config: ConfigResponse = router
+    .wrap()
+    .query_wasm_smart(w.fractionalizer_address, &QueryMsg::Config {})
+    .unwrap();
+  assert_eq!(
+    config,
+    ConfigResponse {
+      owner: w.user_one.to_string(),
+      cw20_code_id: 42,
+      buyout_denom: DENOM.to_string(),
+      auction_duration: 60,
+      shotgun_duration: SHOTGUN_DURATION,
+      max_curator_fee_bps: MAX_CURATOR_FEE_BPS,
+      governance: governance(),
+      supply_limits: SupplyLimits::default(),
+      denom_creation_fee: None,
+    }
+  );
+}
+
 #[test]
 fn test_mint() {
   let router = &mut mock_app();
@@ -319,6 +738,167 @@ fn test_fractionalize() {
   assert_eq!(bal, Uint128::from(2u128));
 }
 
+#[test]
+fn test_fractionalize_owner_validation() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+    w.deployer_address.clone(),
+  );
+  let owner = |address: &Addr, amount: u128| Cw20Coin {
+    address: address.to_string(),
+    amount: Uint128::from(amount),
+  };
+  let mut try_fractionalize = |owners: Vec<Cw20Coin>| {
+    let msg = fractionalize_msg(
+      w.fractionalizer_address.clone(),
+      w.nft_address.clone(),
+      "nft".to_string(),
+      owners,
+    );
+    router
+      .execute(w.deployer_address.clone(), msg)
+      .map_err(|err| err.downcast::<ContractError>().unwrap())
+  };
+
+  assert_eq!(
+    try_fractionalize(vec![]).unwrap_err(),
+    ContractError::NoOwners {}
+  );
+  assert_eq!(
+    try_fractionalize(vec![owner(&w.user_one, 0)]).unwrap_err(),
+    ContractError::ZeroSupply {}
+  );
+  assert_eq!(
+    try_fractionalize(vec![owner(&w.user_one, 1), owner(&w.user_one, 2)]).unwrap_err(),
+    ContractError::DuplicateOwner {
+      address: w.user_one.to_string()
+    }
+  );
+  assert_eq!(
+    try_fractionalize(vec![owner(&Addr::unchecked("NOT_AN_ADDRESS"), 1)]).unwrap_err(),
+    ContractError::InvalidOwner {
+      address: "NOT_AN_ADDRESS".to_string()
+    }
+  );
+
+  // the owner can bound the initial supply
+  let limits = SupplyLimits {
+    min: Some(Uint128::from(10u128)),
+    max: Some(Uint128::from(1000u128)),
+  };
+  let update = |supply_limits: SupplyLimits| {
+    ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+      owner: None,
+      cw20_code_id: None,
+      buyout_denom: None,
+      auction_duration: None,
+      shotgun_duration: None,
+      fees: None,
+      max_curator_fee_bps: None,
+      governance: None,
+      supply_limits: Some(supply_limits),
+      denom_creation_fee: None,
+    })
+  };
+  let err = router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &update(SupplyLimits {
+        min: limits.max,
+        max: limits.min,
+      }),
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::InvalidSupplyLimits {}
+  );
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &update(limits.clone()),
+      &[],
+    )
+    .unwrap();
+
+  let mut try_fractionalize = |owners: Vec<Cw20Coin>| {
+    let msg = fractionalize_msg(
+      w.fractionalizer_address.clone(),
+      w.nft_address.clone(),
+      "nft".to_string(),
+      owners,
+    );
+    router
+      .execute(w.deployer_address.clone(), msg)
+      .map_err(|err| err.downcast::<ContractError>().unwrap())
+  };
+  assert_eq!(
+    try_fractionalize(vec![owner(&w.user_one, 5), owner(&w.user_two, 4)]).unwrap_err(),
+    ContractError::SupplyTooLow {
+      min: limits.min.unwrap()
+    }
+  );
+  assert_eq!(
+    try_fractionalize(vec![owner(&w.user_one, 1001)]).unwrap_err(),
+    ContractError::SupplyTooHigh {
+      max: limits.max.unwrap()
+    }
+  );
+
+  // protocol fee shares count towards the limits
+  let fees = ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+    owner: None,
+    cw20_code_id: None,
+    buyout_denom: None,
+    auction_duration: None,
+    shotgun_duration: None,
+    fees: Some(FeeConfig {
+      recipient: w.deployer_address.to_string(),
+      fractionalize_bps: 1000,
+      flat_fee: None,
+      buyout_bps: 0,
+    }),
+    max_curator_fee_bps: None,
+    governance: None,
+    supply_limits: None,
+    denom_creation_fee: None,
+  });
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &fees,
+      &[],
+    )
+    .unwrap();
+  let mut try_fractionalize = |owners: Vec<Cw20Coin>| {
+    let msg = fractionalize_msg(
+      w.fractionalizer_address.clone(),
+      w.nft_address.clone(),
+      "nft".to_string(),
+      owners,
+    );
+    router
+      .execute(w.deployer_address.clone(), msg)
+      .map_err(|err| err.downcast::<ContractError>().unwrap())
+  };
+  assert_eq!(
+    try_fractionalize(vec![owner(&w.user_one, 1000)]).unwrap_err(),
+    ContractError::SupplyTooHigh {
+      max: limits.max.unwrap()
+    }
+  );
+  try_fractionalize(vec![owner(&w.user_one, 5), owner(&w.user_two, 5)]).unwrap();
+}
+
 #[test]
 fn test_unfractionalize() {
   let router = &mut mock_app();
@@ -421,3 +1001,3587 @@ fn test_unfractionalize() {
   let bal = token_balance(router, cw20, w.fractionalizer_address.to_string());
   assert_eq!(bal, Uint128::from(0u128));
 }
+
+#[test]
+fn test_lock_shares() {
+  let router = &mut mock_app();
+  let w = setup(router);
+
+  let token_id = "nft".to_string();
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    token_id.clone(),
+    w.deployer_address.clone(),
+  );
+  fractionalize(
+    router,
+    w.deployer_address.clone(),
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    token_id.clone(),
+    vec![Cw20Coin {
+      address: w.user_one.to_string(),
+      amount: Uint128::from(3u128),
+    }],
+  );
+  let cw20 = get_fractional_address(
+    router,
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    token_id,
+  );
+  let cw20_address = Addr::unchecked(&cw20);
+  let locked = |router: &mut App| -> Uint128 {
+    let res: LockResponse = router
+      .wrap()
+      .query_wasm_smart(
+        w.fractionalizer_address.clone(),
+        &QueryMsg::Lock {
+          cw20: cw20.clone(),
+          address: w.user_one.to_string(),
+        },
+      )
+      .unwrap();
+    res.amount
+  };
+
+  lock_shares(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    2,
+  )
+  .unwrap();
+  assert_eq!(locked(router), Uint128::from(2u128));
+  assert_eq!(
+    token_balance(router, cw20.clone(), w.user_one.to_string()),
+    Uint128::from(1u128)
+  );
+
+  let unlock = |amount: u128| ExecuteMsg::Unlock {
+    cw20: cw20.clone(),
+    amount: Uint128::from(amount),
+  };
+  let err = router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &unlock(3),
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::InsufficientFunds {}
+  );
+  router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &unlock(2),
+      &[],
+    )
+    .unwrap();
+  assert_eq!(locked(router), Uint128::zero());
+  assert_eq!(
+    token_balance(router, cw20, w.user_one.to_string()),
+    Uint128::from(3u128)
+  );
+}
+
+#[test]
+fn test_fractionalize_requires_custody() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  let fake_code_id = router.store_code(contract_fake_cw721());
+  let fake_address = router
+    .instantiate_contract(
+      fake_code_id,
+      w.deployer_address.clone(),
+      &Empty {},
+      &[],
+      "fake",
+      None,
+    )
+    .unwrap();
+
+  let msg = fractionalize_msg(
+    w.fractionalizer_address.clone(),
+    fake_address,
+    "nft".to_string(),
+    vec![Cw20Coin {
+      address: w.user_one.to_string(),
+      amount: Uint128::from(1u128),
+    }],
+  );
+  // the collection reports the real owner, so the send without a transfer is caught
+  let err = router.execute(w.deployer_address.clone(), msg).unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NftNotHeld {
+      token_id: "nft".to_string()
+    }
+  );
+  let res: VaultsResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::ListVaults {
+        start_after: None,
+        limit: None,
+      },
+    )
+    .unwrap();
+  assert!(res.vaults.is_empty());
+}
+
+// Answers the share token's contract info and cw20 queries with a supply of 3
+fn mock_share_token(
+  querier: &mut MockQuerier,
+  code_id: u64,
+  creator: String,
+  admin: Option<String>,
+  minter: Option<String>,
+) {
+  querier.update_wasm(move |query| {
+    let res = match query {
+      WasmQuery::ContractInfo { .. } => {
+        let mut info = ContractInfoResponse::new(code_id, creator.clone());
+        info.admin = admin.clone();
+        to_binary(&info)
+      }
+      WasmQuery::Smart { msg, .. } => match from_binary(msg).unwrap() {
+        Cw20QueryMsg::Minter {} => to_binary(
+          &minter
+            .clone()
+            .map(|minter| MinterResponse { minter, cap: None }),
+        ),
+        Cw20QueryMsg::TokenInfo {} => to_binary(&TokenInfoResponse {
+          name: "shares".to_string(),
+          symbol: "SHR".to_string(),
+          decimals: 6,
+          total_supply: Uint128::from(3u128),
+        }),
+        _ => panic!("unexpected query"),
+      },
+      _ => panic!("unexpected query"),
+    };
+    SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+  });
+}
+
+#[test]
+fn test_unfractionalize_verifies_share_token() {
+  let mut deps = mock_dependencies();
+  let env = mock_env();
+  instantiate(
+    deps.as_mut(),
+    env.clone(),
+    mock_info("owner", &[]),
+    InstantiateMsg {
+      owner: None,
+      cw20_code_id: 1,
+      buyout_denom: DENOM.to_string(),
+      auction_duration: AUCTION_DURATION,
+      shotgun_duration: SHOTGUN_DURATION,
+      fees: None,
+      max_curator_fee_bps: MAX_CURATOR_FEE_BPS,
+      governance: governance(),
+      supply_limits: None,
+      denom_creation_fee: None,
+    },
+  )
+  .unwrap();
+  vaults()
+    .save(
+      &mut deps.storage,
+      "shares",
+      &Vault {
+        collection: Addr::unchecked("collection"),
+        token_ids: vec!["nft".to_string()],
+        creator: Addr::unchecked("creator"),
+        created_at: env.block.time,
+        status: VaultStatus::Active,
+        mintable: false,
+        backend: ShareBackend::Cw20,
+        cw20_code_id: Some(1),
+      },
+    )
+    .unwrap();
+
+  let contract = env.contract.address.to_string();
+  let impostor = || Some("impostor".to_string());
+  let unfractionalize = ExecuteMsg::Receive(Cw20ReceiveMsg {
+    sender: "holder".to_string(),
+    amount: Uint128::from(3u128),
+    msg: to_binary(&ReceiveMsg::Unfractionalize {
+      recipient: "holder".to_string(),
+    })
+    .unwrap(),
+  });
+  let spoofed = vec![
+    (2, contract.clone(), None, None),
+    (1, "impostor".to_string(), None, None),
+    (1, contract.clone(), impostor(), None),
+    (1, contract.clone(), None, impostor()),
+    // the vault is not mintable, so not even this contract should be the minter
+    (1, contract.clone(), None, Some(contract.clone())),
+  ];
+  for (code_id, creator, admin, minter) in spoofed {
+    mock_share_token(&mut deps.querier, code_id, creator, admin, minter);
+    let err = execute(
+      deps.as_mut(),
+      env.clone(),
+      mock_info("shares", &[]),
+      unfractionalize.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidShareToken {});
+  }
+
+  mock_share_token(&mut deps.querier, 1, contract.clone(), Some(contract), None);
+  let res = execute(
+    deps.as_mut(),
+    env,
+    mock_info("shares", &[]),
+    unfractionalize,
+  )
+  .unwrap();
+  // NFT transfer and share burn
+  assert_eq!(res.messages.len(), 2);
+}
+
+#[test]
+fn test_fractionalize_many_in_one_tx() {
+  let router = &mut mock_app();
+  let w = setup(router);
+
+  let token_ids = vec!["one".to_string(), "two".to_string(), "three".to_string()];
+  for token_id in token_ids.iter() {
+    mint_nft(
+      router,
+      w.deployer_address.clone(),
+      w.nft_address.clone(),
+      token_id.clone(),
+      w.deployer_address.clone(),
+    );
+  }
+
+  let msgs = token_ids
+    .iter()
+    .enumerate()
+    .map(|(i, token_id)| {
+      fractionalize_msg(
+        w.fractionalizer_address.clone(),
+        w.nft_address.clone(),
+        token_id.clone(),
+        vec![Cw20Coin {
+          address: w.user_one.to_string(),
+          amount: Uint128::from(i as u128 + 1),
+        }],
+      )
+    })
+    .collect();
+  router
+    .execute_multi(w.deployer_address.clone(), msgs)
+    .unwrap();
+
+  // every NFT must resolve to its own share token with its own balances
+  let mut seen = vec![];
+  for (i, token_id) in token_ids.into_iter().enumerate() {
+    let cw20 = get_fractional_address(
+      router,
+      w.fractionalizer_address.clone(),
+      w.nft_address.clone(),
+      token_id,
+    );
+    assert!(!seen.contains(&cw20));
+
+    let bal = token_balance(router, cw20.clone(), w.user_one.to_string());
+    assert_eq!(bal, Uint128::from(i as u128 + 1));
+    seen.push(cw20);
+  }
+}
+
+#[test]
+fn test_migrate() {
+  let mut deps = mock_dependencies();
+  let env = mock_env();
+  let contract_name = "crates.io:cw-fractionalize";
+
+  // state as left by 0.0.1
+  cw2::set_contract_version(&mut deps.storage, contract_name, "0.0.1").unwrap();
+  deps.storage.set(
+    b"config",
+    br#"{"last_nft_fractionalized":["collection","nft"]}"#,
+  );
+  LEGACY_CW20_NFT
+    .save(
+      &mut deps.storage,
+      "shares".to_string(),
+      &(Addr::unchecked("collection"), "nft".to_string()),
+    )
+    .unwrap();
+  NFT_CW20
+    .save(
+      &mut deps.storage,
+      (Addr::unchecked("collection"), "nft".to_string()),
+      &"shares".to_string(),
+    )
+    .unwrap();
+
+  let mut config = InstantiateMsg {
+    owner: None,
+    cw20_code_id: 1,
+    buyout_denom: DENOM.to_string(),
+    auction_duration: AUCTION_DURATION,
+    shotgun_duration: SHOTGUN_DURATION,
+    fees: None,
+    max_curator_fee_bps: MAX_CURATOR_FEE_BPS,
+    governance: governance(),
+    supply_limits: None,
+    denom_creation_fee: None,
+  };
+  let err = migrate(deps.as_mut(), env.clone(), MigrateMsg { config: None }).unwrap_err();
+  assert_eq!(
+    err,
+    ContractError::MissingMigrationConfig {
+      from: "0.0.1".to_string()
+    }
+  );
+  let err = migrate(
+    deps.as_mut(),
+    env.clone(),
+    MigrateMsg {
+      config: Some(config.clone()),
+    },
+  )
+  .unwrap_err();
+  assert_eq!(
+    err,
+    ContractError::MissingMigrationConfig {
+      from: "0.0.1".to_string()
+    }
+  );
+
+  config.owner = Some("owner".to_string());
+  migrate(
+    deps.as_mut(),
+    env.clone(),
+    MigrateMsg {
+      config: Some(config),
+    },
+  )
+  .unwrap();
+  assert_eq!(
+    cw2::get_contract_version(&deps.storage).unwrap().version,
+    env!("CARGO_PKG_VERSION")
+  );
+  assert!(!LEGACY_CW20_NFT.has(&deps.storage, "shares".to_string()));
+
+  let res: ConfigResponse =
+    from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+  assert_eq!(res.owner, "owner");
+  assert_eq!(res.cw20_code_id, 1);
+  let res: VaultInfo = from_binary(
+    &query(
+      deps.as_ref(),
+      env.clone(),
+      QueryMsg::VaultByCw20 {
+        cw20: "shares".to_string(),
+      },
+    )
+    .unwrap(),
+  )
+  .unwrap();
+  assert_eq!(res.collection, "collection");
+  assert_eq!(res.token_ids, vec!["nft".to_string()]);
+  assert_eq!(res.status, VaultStatus::Active);
+
+  // already current, nothing left to migrate
+  migrate(deps.as_mut(), env.clone(), MigrateMsg { config: None }).unwrap();
+
+  cw2::set_contract_version(&mut deps.storage, contract_name, "99.0.0").unwrap();
+  let err = migrate(deps.as_mut(), env.clone(), MigrateMsg { config: None }).unwrap_err();
+  assert_eq!(
+    err,
+    ContractError::CannotDowngrade {
+      stored: "99.0.0".to_string(),
+      current: env!("CARGO_PKG_VERSION").to_string(),
+    }
+  );
+
+  cw2::set_contract_version(&mut deps.storage, "crates.io:cw20-base", "0.0.1").unwrap();
+  let err = migrate(deps.as_mut(), env, MigrateMsg { config: None }).unwrap_err();
+  assert_eq!(
+    err,
+    ContractError::WrongContract {
+      contract: "crates.io:cw20-base".to_string()
+    }
+  );
+}
+
+#[test]
+fn test_reply_errors() {
+  let mut deps = mock_dependencies();
+  let pending = PendingVault {
+    collection: Addr::unchecked("collection"),
+    token_ids: vec!["nft".to_string()],
+    creator: Addr::unchecked("creator"),
+    dutch_auction: None,
+    curator_fee_bps: 0,
+    mintable: false,
+    mint_cap: None,
+    backend: ShareBackend::Cw20,
+  };
+
+  let err = reply(
+    deps.as_mut(),
+    mock_env(),
+    Reply {
+      id: 7,
+      result: SubMsgResult::Err("boom".to_string()),
+    },
+  )
+  .unwrap_err();
+  assert_eq!(err, ContractError::UnknownReplyId { id: 7 });
+
+  PENDING_VAULTS.save(&mut deps.storage, 7, &pending).unwrap();
+  let err = reply(
+    deps.as_mut(),
+    mock_env(),
+    Reply {
+      id: 7,
+      result: SubMsgResult::Err("boom".to_string()),
+    },
+  )
+  .unwrap_err();
+  assert_eq!(
+    err,
+    ContractError::ParseReply(ParseReplyError::SubMsgFailure("boom".to_string()))
+  );
+
+  PENDING_VAULTS.save(&mut deps.storage, 8, &pending).unwrap();
+  let err = reply(
+    deps.as_mut(),
+    mock_env(),
+    Reply {
+      id: 8,
+      result: SubMsgResult::Ok(SubMsgResponse {
+        events: vec![],
+        data: None,
+      }),
+    },
+  )
+  .unwrap_err();
+  assert_eq!(
+    err,
+    ContractError::ParseReply(ParseReplyError::ParseFailure(
+      "Missing reply data".to_string()
+    ))
+  );
+
+  // a failed reply must not leave a vault behind
+  assert!(!NFT_CW20.has(
+    &deps.storage,
+    (pending.collection, pending.token_ids[0].clone())
+  ));
+}
+
+#[test]
+fn test_buyout_auction() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  let bidder_one = Addr::unchecked("bidder_one");
+  let bidder_two = Addr::unchecked("bidder_two");
+  fund(router, bidder_one.clone(), 1000);
+  fund(router, bidder_two.clone(), 1000);
+
+  let token_id = "nft".to_string();
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    token_id.clone(),
+    w.deployer_address.clone(),
+  );
+  fractionalize(
+    router,
+    w.deployer_address.clone(),
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    token_id.clone(),
+    vec![
+      Cw20Coin {
+        address: w.user_one.to_string(),
+        amount: Uint128::from(1u128),
+      },
+      Cw20Coin {
+        address: w.user_two.to_string(),
+        amount: Uint128::from(2u128),
+      },
+    ],
+  );
+  let cw20 = get_fractional_address(
+    router,
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    token_id.clone(),
+  );
+  let cw20_address = Addr::unchecked(cw20.clone());
+
+  // no auction before a reserve price has been voted
+  let err = bid(
+    router,
+    bidder_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20.clone(),
+    300,
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NoReservePrice {}
+  );
+
+  // only share holders can vote
+  let err = set_reserve_price(
+    router,
+    bidder_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20.clone(),
+    1,
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NoVotingPower {}
+  );
+
+  // votes are weighted by locked shares
+  let err = set_reserve_price(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20.clone(),
+    100,
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NoVotingPower {}
+  );
+  for (holder, amount) in [(w.user_one.clone(), 1), (w.user_two.clone(), 2)] {
+    lock_shares(
+      router,
+      holder,
+      w.fractionalizer_address.clone(),
+      cw20_address.clone(),
+      amount,
+    )
+    .unwrap();
+  }
+
+  set_reserve_price(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20.clone(),
+    100,
+  )
+  .unwrap();
+  let err = bid(
+    router,
+    bidder_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20.clone(),
+    300,
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::ReserveQuorumNotReached { min_bps: 5000 }
+  );
+
+  // the reserve price is the median weighted by locked shares
+  set_reserve_price(
+    router,
+    w.user_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20.clone(),
+    300,
+  )
+  .unwrap();
+  let res: ReservePriceResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::ReservePrice { cw20: cw20.clone() },
+    )
+    .unwrap();
+  assert_eq!(
+    res,
+    ReservePriceResponse {
+      price: Some(Uint128::from(300u128)),
+      voted_shares: Uint128::from(3u128),
+      quorum_reached: true,
+    }
+  );
+
+  // unlocking takes the shares out of the vote
+  let unlock = ExecuteMsg::Unlock {
+    cw20: cw20.clone(),
+    amount: Uint128::from(1u128),
+  };
+  router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &unlock,
+      &[],
+    )
+    .unwrap();
+  let err = bid(
+    router,
+    bidder_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20.clone(),
+    99,
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::BidTooLow {
+      min_bid: Uint128::from(100u128)
+    }
+  );
+  lock_shares(
+    router,
+    w.user_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    1,
+  )
+  .unwrap();
+
+  let err = bid(
+    router,
+    bidder_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20.clone(),
+    299,
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::BidTooLow {
+      min_bid: Uint128::from(300u128)
+    }
+  );
+  bid(
+    router,
+    bidder_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20.clone(),
+    300,
+  )
+  .unwrap();
+
+  // later bids, refunds and proceeds stay in the denom the auction started in
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        owner: None,
+        cw20_code_id: None,
+        buyout_denom: Some("uother".to_string()),
+        auction_duration: None,
+        shotgun_duration: None,
+        fees: None,
+        max_curator_fee_bps: None,
+        governance: None,
+        supply_limits: None,
+        denom_creation_fee: None,
+      }),
+      &[],
+    )
+    .unwrap();
+
+  // the vault is locked while the auction runs
+  router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::Unlock {
+        cw20: cw20.clone(),
+        amount: Uint128::from(2u128),
+      },
+      &[],
+    )
+    .unwrap();
+  let err = unfractionalize(
+    router,
+    w.user_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    Uint128::from(2u128),
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::AuctionInProgress {}
+  );
+
+  let err = bid(
+    router,
+    bidder_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20.clone(),
+    314,
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::BidTooLow {
+      min_bid: Uint128::from(315u128)
+    }
+  );
+  bid(
+    router,
+    bidder_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20.clone(),
+    315,
+  )
+  .unwrap();
+  assert_eq!(native_balance(router, bidder_one), Uint128::from(1000u128));
+
+  let msg = ExecuteMsg::SettleAuction { cw20: cw20.clone() };
+  let err = router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::AuctionNotEnded {}
+  );
+
+  router.update_block(|block| block.time = block.time.plus_seconds(AUCTION_DURATION));
+  router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &[],
+    )
+    .unwrap();
+
+  let owner_of = nft_owner_of(router, w.nft_address.to_string(), token_id);
+  assert_eq!(owner_of, bidder_two.to_string());
+
+  // share holders redeem the proceeds pro-rata
+  router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::Unlock {
+        cw20: cw20.clone(),
+        amount: Uint128::from(1u128),
+      },
+      &[],
+    )
+    .unwrap();
+  redeem(
+    router,
+    w.user_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    Uint128::from(2u128),
+  )
+  .unwrap();
+  assert_eq!(
+    native_balance(router, w.user_two.clone()),
+    Uint128::from(210u128)
+  );
+
+  redeem(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address,
+    Uint128::from(1u128),
+  )
+  .unwrap();
+  assert_eq!(
+    native_balance(router, w.user_one.clone()),
+    Uint128::from(105u128)
+  );
+  assert_eq!(
+    native_balance(router, w.fractionalizer_address.clone()),
+    Uint128::zero()
+  );
+
+  let bal = token_balance(router, cw20, w.fractionalizer_address.to_string());
+  assert_eq!(bal, Uint128::zero());
+}
+
+fn setup_vault(router: &mut App, w: &World, token_id: &str) -> Addr {
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    token_id.to_string(),
+    w.deployer_address.clone(),
+  );
+  fractionalize(
+    router,
+    w.deployer_address.clone(),
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    token_id.to_string(),
+    vec![
+      Cw20Coin {
+        address: w.user_one.to_string(),
+        amount: Uint128::from(1u128),
+      },
+      Cw20Coin {
+        address: w.user_two.to_string(),
+        amount: Uint128::from(2u128),
+      },
+    ],
+  );
+  let cw20 = get_fractional_address(
+    router,
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    token_id.to_string(),
+  );
+  Addr::unchecked(cw20)
+}
+
+#[test]
+fn test_shotgun_countered() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  fund(router, w.user_one.clone(), 1000);
+  fund(router, w.user_two.clone(), 1000);
+  let cw20_address = setup_vault(router, &w, "nft");
+
+  // user_one offers 50 per share for the 2 shares of user_two
+  let err = start_shotgun(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    1,
+    50,
+    99,
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::IncorrectPayment {
+      expected: Uint128::from(100u128)
+    }
+  );
+  start_shotgun(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    1,
+    50,
+    100,
+  )
+  .unwrap();
+
+  let bal = token_balance(
+    router,
+    cw20_address.to_string(),
+    w.fractionalizer_address.to_string(),
+  );
+  assert_eq!(bal, Uint128::from(1u128));
+
+  // the offer stays in the denom it was escrowed in
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        owner: None,
+        cw20_code_id: None,
+        buyout_denom: Some("uother".to_string()),
+        auction_duration: None,
+        shotgun_duration: None,
+        fees: None,
+        max_curator_fee_bps: None,
+        governance: None,
+        supply_limits: None,
+        denom_creation_fee: None,
+      }),
+      &[],
+    )
+    .unwrap();
+
+  // user_two turns the offer around and buys user_one out at the same price
+  let msg = ExecuteMsg::CounterShotgun {
+    cw20: cw20_address.to_string(),
+  };
+  router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &coins(50, DENOM),
+    )
+    .unwrap();
+
+  assert_eq!(
+    native_balance(router, w.user_one.clone()),
+    Uint128::from(1050u128)
+  );
+  assert_eq!(
+    native_balance(router, w.user_two.clone()),
+    Uint128::from(950u128)
+  );
+  let bal = token_balance(router, cw20_address.to_string(), w.user_two.to_string());
+  assert_eq!(bal, Uint128::from(3u128));
+
+  let msg = ExecuteMsg::CompleteShotgun {
+    cw20: cw20_address.to_string(),
+  };
+  let err = router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NoShotgun {}
+  );
+
+  unfractionalize(
+    router,
+    w.user_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address,
+    Uint128::from(3u128),
+  )
+  .unwrap();
+  let owner_of = nft_owner_of(router, w.nft_address.to_string(), "nft".to_string());
+  assert_eq!(owner_of, w.user_two.to_string());
+}
+
+#[test]
+fn test_shotgun_completed() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  fund(router, w.user_one.clone(), 1000);
+  let cw20_address = setup_vault(router, &w, "nft");
+
+  start_shotgun(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    1,
+    50,
+    100,
+  )
+  .unwrap();
+
+  // no auction can race the offer
+  let err = bid(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.to_string(),
+    100,
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::ShotgunInProgress {}
+  );
+
+  let msg = ExecuteMsg::CompleteShotgun {
+    cw20: cw20_address.to_string(),
+  };
+  let err = router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::ShotgunNotExpired {}
+  );
+
+  router.update_block(|block| block.time = block.time.plus_seconds(SHOTGUN_DURATION));
+  router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &[],
+    )
+    .unwrap();
+
+  let owner_of = nft_owner_of(router, w.nft_address.to_string(), "nft".to_string());
+  assert_eq!(owner_of, w.user_one.to_string());
+
+  // the remaining holder sells at the offered price
+  redeem(
+    router,
+    w.user_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    Uint128::from(2u128),
+  )
+  .unwrap();
+  assert_eq!(
+    native_balance(router, w.user_two.clone()),
+    Uint128::from(100u128)
+  );
+
+  let res: cw20::TokenInfoResponse = router
+    .wrap()
+    .query_wasm_smart(cw20_address, &Cw20QueryMsg::TokenInfo {})
+    .unwrap();
+  assert_eq!(res.total_supply, Uint128::zero());
+}
+
+fn setup_dutch_auction_vault(
+  router: &mut App,
+  w: &World,
+  token_id: &str,
+  params: DutchAuctionParams,
+) -> Addr {
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    token_id.to_string(),
+    w.deployer_address.clone(),
+  );
+  let msg = send_nft_msg(
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    token_id.to_string(),
+    &ReceiveMsg::Fractionalize {
+      owners: vec![
+        Cw20Coin {
+          address: w.user_one.to_string(),
+          amount: Uint128::from(1u128),
+        },
+        Cw20Coin {
+          address: w.user_two.to_string(),
+          amount: Uint128::from(2u128),
+        },
+      ],
+      name: Some("name".to_string()),
+      symbol: Some("symbol".to_string()),
+      dutch_auction: Some(params),
+      curator_fee_bps: None,
+      mintable: None,
+      mint_cap: None,
+      share_token: None,
+      backend: None,
+    },
+  );
+  router.execute(w.deployer_address.clone(), msg).unwrap();
+
+  let cw20 = get_fractional_address(
+    router,
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    token_id.to_string(),
+  );
+  Addr::unchecked(cw20)
+}
+
+fn dutch_auction_price(router: &mut App, w: &World, cw20_address: &Addr) -> Uint128 {
+  let res: Option<DutchAuctionResponse> = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::DutchAuction {
+        cw20: cw20_address.to_string(),
+      },
+    )
+    .unwrap();
+  res.unwrap().current_price
+}
+
+#[test]
+fn test_dutch_auction() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  let buyer = Addr::unchecked("buyer");
+  fund(router, buyer.clone(), 1000);
+
+  let cw20_address = setup_dutch_auction_vault(
+    router,
+    &w,
+    "nft",
+    DutchAuctionParams {
+      start_price: Uint128::from(1000u128),
+      floor_price: Uint128::from(200u128),
+      duration: 800,
+      decay: PriceDecay::Linear,
+    },
+  );
+  assert_eq!(
+    dutch_auction_price(router, &w, &cw20_address),
+    Uint128::from(1000u128)
+  );
+
+  router.update_block(|block| block.time = block.time.plus_seconds(400));
+  assert_eq!(
+    dutch_auction_price(router, &w, &cw20_address),
+    Uint128::from(600u128)
+  );
+
+  let msg = ExecuteMsg::BuyNow {
+    cw20: cw20_address.to_string(),
+  };
+  let err = router
+    .execute_contract(
+      buyer.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &coins(599, DENOM),
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::IncorrectPayment {
+      expected: Uint128::from(600u128)
+    }
+  );
+
+  // overpayment is refunded
+  router
+    .execute_contract(
+      buyer.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &coins(650, DENOM),
+    )
+    .unwrap();
+  assert_eq!(
+    native_balance(router, buyer.clone()),
+    Uint128::from(400u128)
+  );
+
+  let owner_of = nft_owner_of(router, w.nft_address.to_string(), "nft".to_string());
+  assert_eq!(owner_of, buyer.to_string());
+
+  let res: Option<DutchAuctionResponse> = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::DutchAuction {
+        cw20: cw20_address.to_string(),
+      },
+    )
+    .unwrap();
+  assert_eq!(res, None);
+
+  redeem(
+    router,
+    w.user_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    Uint128::from(2u128),
+  )
+  .unwrap();
+  assert_eq!(
+    native_balance(router, w.user_two.clone()),
+    Uint128::from(400u128)
+  );
+  redeem(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address,
+    Uint128::from(1u128),
+  )
+  .unwrap();
+  assert_eq!(
+    native_balance(router, w.user_one.clone()),
+    Uint128::from(200u128)
+  );
+}
+
+#[test]
+fn test_dutch_auction_exponential_decay() {
+  let router = &mut mock_app();
+  let w = setup(router);
+
+  let cw20_address = setup_dutch_auction_vault(
+    router,
+    &w,
+    "nft",
+    DutchAuctionParams {
+      start_price: Uint128::from(1100u128),
+      floor_price: Uint128::from(100u128),
+      duration: 1000,
+      decay: PriceDecay::Exponential { half_life: 100 },
+    },
+  );
+
+  router.update_block(|block| block.time = block.time.plus_seconds(100));
+  assert_eq!(
+    dutch_auction_price(router, &w, &cw20_address),
+    Uint128::from(600u128)
+  );
+
+  // halfway between two halvings
+  router.update_block(|block| block.time = block.time.plus_seconds(50));
+  assert_eq!(
+    dutch_auction_price(router, &w, &cw20_address),
+    Uint128::from(475u128)
+  );
+
+  router.update_block(|block| block.time = block.time.plus_seconds(850));
+  assert_eq!(
+    dutch_auction_price(router, &w, &cw20_address),
+    Uint128::from(100u128)
+  );
+
+  // unfractionalizing closes the auction
+  token_transfer(
+    router,
+    w.user_two.clone(),
+    cw20_address.clone(),
+    Uint128::from(2u128),
+    w.user_one.clone(),
+  );
+  unfractionalize(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    Uint128::from(3u128),
+  )
+  .unwrap();
+
+  fund(router, w.user_one.clone(), 100);
+  let msg = ExecuteMsg::BuyNow {
+    cw20: cw20_address.to_string(),
+  };
+  let err = router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &coins(100, DENOM),
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NoDutchAuction {}
+  );
+}
+
+#[test]
+fn test_vault_registry() {
+  let router = &mut mock_app();
+  let w = setup(router);
+
+  // a second collection to filter on
+  let msg = Cw721InstantiateMsg {
+    minter: w.deployer_address.to_string(),
+    name: "Other NFT".to_string(),
+    symbol: "OTHER".to_string(),
+  };
+  let other_nft_address = router
+    .instantiate_contract(
+      w.cw721_code_id,
+      w.deployer_address.clone(),
+      &msg,
+      &[],
+      "other",
+      None,
+    )
+    .unwrap();
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    other_nft_address.clone(),
+    "other".to_string(),
+    w.user_two.clone(),
+  );
+  fractionalize(
+    router,
+    w.user_two.clone(),
+    w.fractionalizer_address.clone(),
+    other_nft_address.clone(),
+    "other".to_string(),
+    vec![Cw20Coin {
+      address: w.user_two.to_string(),
+      amount: Uint128::from(1u128),
+    }],
+  );
+
+  let one = setup_vault(router, &w, "one");
+  let two = setup_vault(router, &w, "two");
+
+  let res: VaultsResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::ListVaults {
+        start_after: None,
+        limit: None,
+      },
+    )
+    .unwrap();
+  assert_eq!(res.vaults.len(), 3);
+
+  // paginating yields every vault exactly once
+  let res: VaultsResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::ListVaults {
+        start_after: None,
+        limit: Some(2),
+      },
+    )
+    .unwrap();
+  assert_eq!(res.vaults.len(), 2);
+  let page: VaultsResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::ListVaults {
+        start_after: Some(res.vaults[1].cw20.clone()),
+        limit: Some(2),
+      },
+    )
+    .unwrap();
+  assert_eq!(page.vaults.len(), 1);
+  assert!(!res.vaults.contains(&page.vaults[0]));
+
+  let res: VaultsResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::VaultsByCollection {
+        collection: w.nft_address.to_string(),
+        start_after: None,
+        limit: None,
+      },
+    )
+    .unwrap();
+  let mut cw20s: Vec<String> = res.vaults.iter().map(|v| v.cw20.clone()).collect();
+  cw20s.sort();
+  let mut expected = vec![one.to_string(), two.to_string()];
+  expected.sort();
+  assert_eq!(cw20s, expected);
+
+  let res: VaultsResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::VaultsByCollection {
+        collection: other_nft_address.to_string(),
+        start_after: None,
+        limit: None,
+      },
+    )
+    .unwrap();
+  assert_eq!(res.vaults.len(), 1);
+  assert_eq!(res.vaults[0].creator, w.user_two.to_string());
+
+  // the status follows the vault through its life
+  token_transfer(
+    router,
+    w.user_two.clone(),
+    one.clone(),
+    Uint128::from(2u128),
+    w.user_one.clone(),
+  );
+  unfractionalize(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    one.clone(),
+    Uint128::from(3u128),
+  )
+  .unwrap();
+
+  let vault: VaultInfo = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::VaultByCw20 {
+        cw20: one.to_string(),
+      },
+    )
+    .unwrap();
+  assert_eq!(vault.collection, w.nft_address.to_string());
+  assert_eq!(vault.token_ids, vec!["one".to_string()]);
+  assert_eq!(vault.creator, w.deployer_address.to_string());
+  assert_eq!(vault.status, VaultStatus::Unfractionalized);
+}
+
+#[test]
+fn test_get_cw20_address_errors() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  let cw20_address = setup_vault(router, &w, "nft");
+
+  // invalid collection addresses are rejected instead of aborting
+  let err = router
+    .wrap()
+    .query_wasm_smart::<GetCw20AddressResponse>(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::GetCw20Address {
+        address: "INVALID".to_string(),
+        token_id: "nft".to_string(),
+      },
+    )
+    .unwrap_err();
+  assert!(err.to_string().contains("Invalid input"));
+
+  let err = router
+    .wrap()
+    .query_wasm_smart::<TryGetCw20AddressResponse>(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::TryGetCw20Address {
+        address: "INVALID".to_string(),
+        token_id: "nft".to_string(),
+      },
+    )
+    .unwrap_err();
+  assert!(err.to_string().contains("Invalid input"));
+
+  // missing vaults
+  let err = router
+    .wrap()
+    .query_wasm_smart::<GetCw20AddressResponse>(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::GetCw20Address {
+        address: w.nft_address.to_string(),
+        token_id: "missing".to_string(),
+      },
+    )
+    .unwrap_err();
+  assert!(err.to_string().contains("is not fractionalized"));
+
+  let res: TryGetCw20AddressResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::TryGetCw20Address {
+        address: w.nft_address.to_string(),
+        token_id: "missing".to_string(),
+      },
+    )
+    .unwrap();
+  assert_eq!(
+    res,
+    TryGetCw20AddressResponse {
+      address: None,
+      is_fractionalized: false,
+    }
+  );
+
+  let res: TryGetCw20AddressResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::TryGetCw20Address {
+        address: w.nft_address.to_string(),
+        token_id: "nft".to_string(),
+      },
+    )
+    .unwrap();
+  assert_eq!(
+    res,
+    TryGetCw20AddressResponse {
+      address: Some(cw20_address.to_string()),
+      is_fractionalized: true,
+    }
+  );
+}
+
+fn vault_assets(router: &mut App, w: &World, cw20_address: &Addr) -> VaultAssetsResponse {
+  router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::VaultAssets {
+        cw20: cw20_address.to_string(),
+      },
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_redeem_deposited_assets() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  fund(router, w.deployer_address.clone(), 300);
+  let cw20_address = setup_vault(router, &w, "nft");
+  let other_cw20_address = setup_vault(router, &w, "other");
+
+  // nothing to redeem yet
+  let err = redeem(
+    router,
+    w.user_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    Uint128::from(1u128),
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NothingToRedeem {}
+  );
+
+  let msg = ExecuteMsg::Deposit {
+    cw20: cw20_address.to_string(),
+  };
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &coins(300, DENOM),
+    )
+    .unwrap();
+
+  let msg = Cw20ExecuteMsg::Send {
+    contract: w.fractionalizer_address.to_string(),
+    amount: Uint128::from(2u128),
+    msg: to_binary(&ReceiveMsg::Deposit {
+      cw20: cw20_address.to_string(),
+    })
+    .unwrap(),
+  };
+  router
+    .execute_contract(w.user_two.clone(), other_cw20_address.clone(), &msg, &[])
+    .unwrap();
+
+  // a vault's own shares cannot be deposited into it
+  let msg = Cw20ExecuteMsg::Send {
+    contract: w.fractionalizer_address.to_string(),
+    amount: Uint128::from(1u128),
+    msg: to_binary(&ReceiveMsg::Deposit {
+      cw20: cw20_address.to_string(),
+    })
+    .unwrap(),
+  };
+  let err = router
+    .execute_contract(w.user_one.clone(), cw20_address.clone(), &msg, &[])
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::InvalidDeposit {}
+  );
+
+  assert_eq!(
+    vault_assets(router, &w, &cw20_address),
+    VaultAssetsResponse {
+      native: coins(300, DENOM),
+      cw20: vec![Cw20Coin {
+        address: other_cw20_address.to_string(),
+        amount: Uint128::from(2u128),
+      }],
+    }
+  );
+
+  // user_two exits with two thirds of everything
+  redeem(
+    router,
+    w.user_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    Uint128::from(2u128),
+  )
+  .unwrap();
+  assert_eq!(
+    native_balance(router, w.user_two.clone()),
+    Uint128::from(200u128)
+  );
+  assert_eq!(
+    token_balance(
+      router,
+      other_cw20_address.to_string(),
+      w.user_two.to_string()
+    ),
+    Uint128::from(1u128)
+  );
+  assert_eq!(
+    token_balance(router, cw20_address.to_string(), w.user_two.to_string()),
+    Uint128::zero()
+  );
+
+  // the last holder keeps the NFT claim and has to unfractionalize
+  let err = redeem(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    Uint128::from(1u128),
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::RedeemWholeSupply {}
+  );
+
+  unfractionalize(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    Uint128::from(1u128),
+  )
+  .unwrap();
+
+  let owner_of = nft_owner_of(router, w.nft_address.to_string(), "nft".to_string());
+  assert_eq!(owner_of, w.user_one.to_string());
+  assert_eq!(
+    native_balance(router, w.user_one.clone()),
+    Uint128::from(100u128)
+  );
+  assert_eq!(
+    token_balance(
+      router,
+      other_cw20_address.to_string(),
+      w.user_one.to_string()
+    ),
+    Uint128::from(2u128)
+  );
+  assert_eq!(
+    vault_assets(router, &w, &cw20_address),
+    VaultAssetsResponse {
+      native: vec![],
+      cw20: vec![],
+    }
+  );
+}
+
+#[test]
+fn test_failing_deposit_does_not_block_exit() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  let cw20_address = setup_vault(router, &w, "nft");
+  let other_cw20_address = setup_vault(router, &w, "other");
+
+  // any contract can claim to deposit a cw20, here one that cannot be transferred
+  let deposit = ExecuteMsg::Receive(Cw20ReceiveMsg {
+    sender: w.user_one.to_string(),
+    amount: Uint128::from(300u128),
+    msg: to_binary(&ReceiveMsg::Deposit {
+      cw20: cw20_address.to_string(),
+    })
+    .unwrap(),
+  });
+  router
+    .execute_contract(
+      Addr::unchecked("broken_token"),
+      w.fractionalizer_address.clone(),
+      &deposit,
+      &[],
+    )
+    .unwrap();
+  let msg = Cw20ExecuteMsg::Send {
+    contract: w.fractionalizer_address.to_string(),
+    amount: Uint128::from(2u128),
+    msg: to_binary(&ReceiveMsg::Deposit {
+      cw20: cw20_address.to_string(),
+    })
+    .unwrap(),
+  };
+  router
+    .execute_contract(w.user_two.clone(), other_cw20_address.clone(), &msg, &[])
+    .unwrap();
+
+  // the other assets are still paid out
+  redeem(
+    router,
+    w.user_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    Uint128::from(2u128),
+  )
+  .unwrap();
+  assert_eq!(
+    token_balance(
+      router,
+      other_cw20_address.to_string(),
+      w.user_two.to_string()
+    ),
+    Uint128::from(1u128)
+  );
+  unfractionalize(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address,
+    Uint128::from(1u128),
+  )
+  .unwrap();
+
+  let owner_of = nft_owner_of(router, w.nft_address.to_string(), "nft".to_string());
+  assert_eq!(owner_of, w.user_one.to_string());
+  assert_eq!(
+    token_balance(
+      router,
+      other_cw20_address.to_string(),
+      w.user_one.to_string()
+    ),
+    Uint128::from(2u128)
+  );
+
+  // the failed transfers are credited to their recipients, who can retry them
+  let claims = |router: &mut App, address: &Addr| -> Vec<Cw20Coin> {
+    let res: AssetClaimsResponse = router
+      .wrap()
+      .query_wasm_smart(
+        w.fractionalizer_address.clone(),
+        &QueryMsg::AssetClaims {
+          address: address.to_string(),
+        },
+      )
+      .unwrap();
+    res.claims
+  };
+  let broken_claim = |amount: u128| {
+    vec![Cw20Coin {
+      address: "broken_token".to_string(),
+      amount: Uint128::from(amount),
+    }]
+  };
+  assert_eq!(claims(router, &w.user_two), broken_claim(200));
+  assert_eq!(claims(router, &w.user_one), broken_claim(100));
+  let claim = ExecuteMsg::ClaimAsset {
+    token: "broken_token".to_string(),
+  };
+  router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &claim,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(claims(router, &w.user_one), broken_claim(100));
+  let err = router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &claim,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NoAssetClaim {}
+  );
+
+  // a vault only takes a bounded number of different tokens
+  let deposit = ExecuteMsg::Receive(Cw20ReceiveMsg {
+    sender: w.user_one.to_string(),
+    amount: Uint128::from(1u128),
+    msg: to_binary(&ReceiveMsg::Deposit {
+      cw20: other_cw20_address.to_string(),
+    })
+    .unwrap(),
+  });
+  let mut deposit_token = |token: String| {
+    router.execute_contract(
+      Addr::unchecked(token),
+      w.fractionalizer_address.clone(),
+      &deposit,
+      &[],
+    )
+  };
+  for i in 0..10 {
+    deposit_token(format!("token_{}", i)).unwrap();
+  }
+  let err = deposit_token("token_10".to_string()).unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::TooManyDepositTokens { max: 10 }
+  );
+  deposit_token("token_0".to_string()).unwrap();
+}
+
+#[test]
+fn test_basket_vault() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  for token_id in ["a", "b", "c"] {
+    mint_nft(
+      router,
+      w.deployer_address.clone(),
+      w.nft_address.clone(),
+      token_id.to_string(),
+      w.deployer_address.clone(),
+    );
+  }
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    "stranger".to_string(),
+    w.user_one.clone(),
+  );
+
+  let open = ExecuteMsg::OpenBasket {
+    collection: w.nft_address.to_string(),
+  };
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &open,
+      &[],
+    )
+    .unwrap();
+
+  // only the creator fills their basket
+  let msg = send_nft_msg(
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "stranger".to_string(),
+    &ReceiveMsg::DepositToBasket { basket_id: 1 },
+  );
+  let err = router.execute(w.user_one.clone(), msg).unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::Unauthorized {}
+  );
+
+  let finalize = ExecuteMsg::FinalizeBasket(FinalizeBasketMsg {
+    basket_id: 1,
+    owners: vec![Cw20Coin {
+      address: w.user_one.to_string(),
+      amount: Uint128::from(3u128),
+    }],
+    name: Some("basket".to_string()),
+    symbol: Some("BSKT".to_string()),
+    dutch_auction: None,
+    curator_fee_bps: None,
+    mintable: None,
+    mint_cap: None,
+    share_token: None,
+    backend: None,
+  });
+  let err = router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &finalize,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::EmptyBasket {}
+  );
+
+  for token_id in ["a", "b"] {
+    let msg = send_nft_msg(
+      w.fractionalizer_address.clone(),
+      w.nft_address.clone(),
+      token_id.to_string(),
+      &ReceiveMsg::DepositToBasket { basket_id: 1 },
+    );
+    router.execute(w.deployer_address.clone(), msg).unwrap();
+  }
+
+  let basket: Option<BasketResponse> = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::Basket { basket_id: 1 },
+    )
+    .unwrap();
+  assert_eq!(
+    basket,
+    Some(BasketResponse {
+      creator: w.deployer_address.to_string(),
+      collection: w.nft_address.to_string(),
+      token_ids: vec!["a".to_string(), "b".to_string()],
+    })
+  );
+
+  let err = router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &finalize,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::Unauthorized {}
+  );
+
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &finalize,
+      &[],
+    )
+    .unwrap();
+
+  // every NFT of the basket maps to the same share token
+  let cw20_address = get_fractional_address(
+    router,
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "a".to_string(),
+  );
+  assert_eq!(
+    get_fractional_address(
+      router,
+      w.fractionalizer_address.clone(),
+      w.nft_address.clone(),
+      "b".to_string(),
+    ),
+    cw20_address
+  );
+  let vault: VaultInfo = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::VaultByCw20 {
+        cw20: cw20_address.clone(),
+      },
+    )
+    .unwrap();
+  assert_eq!(vault.token_ids, vec!["a".to_string(), "b".to_string()]);
+
+  let err = router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &finalize,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::BasketNotFound { id: 1 }
+  );
+
+  // a cancelled draft hands its NFTs back
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &open,
+      &[],
+    )
+    .unwrap();
+  let msg = send_nft_msg(
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "c".to_string(),
+    &ReceiveMsg::DepositToBasket { basket_id: 2 },
+  );
+  router.execute(w.deployer_address.clone(), msg).unwrap();
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::CancelBasket { basket_id: 2 },
+      &[],
+    )
+    .unwrap();
+  let owner_of = nft_owner_of(router, w.nft_address.to_string(), "c".to_string());
+  assert_eq!(owner_of, w.deployer_address.to_string());
+
+  // unfractionalizing returns the whole basket
+  unfractionalize(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    Addr::unchecked(cw20_address),
+    Uint128::from(3u128),
+  )
+  .unwrap();
+  for token_id in ["a", "b"] {
+    let owner_of = nft_owner_of(router, w.nft_address.to_string(), token_id.to_string());
+    assert_eq!(owner_of, w.user_one.to_string());
+  }
+}
+
+#[test]
+fn test_protocol_fees() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  let fee_recipient = Addr::unchecked("fee_recipient");
+  let fees = FeeConfig {
+    recipient: fee_recipient.to_string(),
+    fractionalize_bps: 100,
+    flat_fee: Some(coin(10, DENOM)),
+    buyout_bps: 500,
+  };
+
+  let update = |fees: FeeConfig| {
+    ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+      owner: None,
+      cw20_code_id: None,
+      buyout_denom: None,
+      auction_duration: None,
+      shotgun_duration: None,
+      fees: Some(fees),
+      max_curator_fee_bps: None,
+      governance: None,
+      supply_limits: None,
+      denom_creation_fee: None,
+    })
+  };
+  let err = router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &update(FeeConfig {
+        buyout_bps: 10_001,
+        ..fees.clone()
+      }),
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::InvalidFee {}
+  );
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &update(fees.clone()),
+      &[],
+    )
+    .unwrap();
+
+  let res: Option<FeeConfig> = router
+    .wrap()
+    .query_wasm_smart(w.fractionalizer_address.clone(), &QueryMsg::Fees {})
+    .unwrap();
+  assert_eq!(res, Some(fees));
+
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+    w.deployer_address.clone(),
+  );
+  let owners = vec![
+    Cw20Coin {
+      address: w.user_one.to_string(),
+      amount: Uint128::from(100u128),
+    },
+    Cw20Coin {
+      address: w.user_two.to_string(),
+      amount: Uint128::from(100u128),
+    },
+  ];
+
+  // the flat fee has to be prepaid
+  let msg = fractionalize_msg(
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+    owners.clone(),
+  );
+  let err = router.execute(w.deployer_address.clone(), msg).unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::FeeNotPaid {
+      fee: coin(10, DENOM)
+    }
+  );
+
+  fund(router, w.deployer_address.clone(), 15);
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::PayFee {},
+      &coins(15, DENOM),
+    )
+    .unwrap();
+  fractionalize(
+    router,
+    w.deployer_address.clone(),
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+    owners,
+  );
+
+  // whatever was paid on top of the fee can be withdrawn
+  let fee_credit = |router: &mut App| -> FeeCreditResponse {
+    router
+      .wrap()
+      .query_wasm_smart(
+        w.fractionalizer_address.clone(),
+        &QueryMsg::FeeCredit {
+          address: w.deployer_address.to_string(),
+        },
+      )
+      .unwrap()
+  };
+  assert_eq!(fee_credit(router).credit, coins(5, DENOM));
+  let withdraw = ExecuteMsg::WithdrawFeeCredit {};
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &withdraw,
+      &[],
+    )
+    .unwrap();
+  assert_eq!(
+    native_balance(router, w.deployer_address.clone()),
+    Uint128::from(5u128)
+  );
+  assert_eq!(fee_credit(router).credit, vec![]);
+  let err = router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &withdraw,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NoFeeCredit {}
+  );
+  let cw20_address = get_fractional_address(
+    router,
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+  );
+  assert_eq!(
+    native_balance(router, fee_recipient.clone()),
+    Uint128::from(10u128)
+  );
+  assert_eq!(
+    token_balance(router, cw20_address.clone(), fee_recipient.to_string()),
+    Uint128::from(2u128)
+  );
+
+  // buyouts pay the fee out of the proceeds
+  fund(router, w.user_one.clone(), 1000);
+  for holder in [w.user_one.clone(), w.user_two.clone()] {
+    lock_shares(
+      router,
+      holder.clone(),
+      w.fractionalizer_address.clone(),
+      Addr::unchecked(&cw20_address),
+      100,
+    )
+    .unwrap();
+    set_reserve_price(
+      router,
+      holder,
+      w.fractionalizer_address.clone(),
+      cw20_address.clone(),
+      1000,
+    )
+    .unwrap();
+  }
+  bid(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    1000,
+  )
+  .unwrap();
+  router.update_block(|block| block.time = block.time.plus_seconds(AUCTION_DURATION));
+  router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::SettleAuction {
+        cw20: cw20_address.clone(),
+      },
+      &[],
+    )
+    .unwrap();
+
+  assert_eq!(native_balance(router, fee_recipient), Uint128::from(60u128));
+  let assets: VaultAssetsResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::VaultAssets { cw20: cw20_address },
+    )
+    .unwrap();
+  assert_eq!(assets.native, coins(950, DENOM));
+}
+
+#[test]
+fn test_curator_fee() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+    w.deployer_address.clone(),
+  );
+  let curated = |curator_fee_bps: u64| {
+    send_nft_msg(
+      w.fractionalizer_address.clone(),
+      w.nft_address.clone(),
+      "nft".to_string(),
+      &ReceiveMsg::Fractionalize {
+        owners: vec![Cw20Coin {
+          address: w.user_one.to_string(),
+          amount: Uint128::from(1_000_000u128),
+        }],
+        name: Some("name".to_string()),
+        symbol: Some("symbol".to_string()),
+        dutch_auction: None,
+        curator_fee_bps: Some(curator_fee_bps),
+        mintable: None,
+        mint_cap: None,
+        share_token: None,
+        backend: None,
+      },
+    )
+  };
+
+  let err = router
+    .execute(w.deployer_address.clone(), curated(MAX_CURATOR_FEE_BPS + 1))
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::CuratorFeeTooHigh {
+      max: MAX_CURATOR_FEE_BPS
+    }
+  );
+  router
+    .execute(w.deployer_address.clone(), curated(500))
+    .unwrap();
+  let cw20_address = get_fractional_address(
+    router,
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+  );
+
+  // a year at 5% accrues 5% of the supply
+  router.update_block(|block| block.time = block.time.plus_seconds(SECONDS_PER_YEAR));
+  let curator: Option<CuratorResponse> = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::Curator {
+        cw20: cw20_address.clone(),
+      },
+    )
+    .unwrap();
+  let curator = curator.unwrap();
+  assert_eq!(curator.curator, w.deployer_address.to_string());
+  assert_eq!(curator.claimable, Uint128::from(50_000u128));
+
+  let msg = ExecuteMsg::ClaimCuratorFee {
+    cw20: cw20_address.clone(),
+  };
+  let err = router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::Unauthorized {}
+  );
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &[],
+    )
+    .unwrap();
+  assert_eq!(
+    token_balance(router, cw20_address.clone(), w.deployer_address.to_string()),
+    Uint128::from(50_000u128)
+  );
+
+  // nothing more accrues within the same block
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &[],
+    )
+    .unwrap();
+  assert_eq!(
+    token_balance(router, cw20_address, w.deployer_address.to_string()),
+    Uint128::from(50_000u128)
+  );
+
+  // vaults without a curator fee have no minter to claim from
+  let other = setup_vault(router, &w, "other");
+  let err = router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::ClaimCuratorFee {
+        cw20: other.to_string(),
+      },
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NoCurator {}
+  );
+}
+
+#[test]
+fn test_mintable_shares() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+    w.deployer_address.clone(),
+  );
+  let mintable = |mint_cap: u128| {
+    send_nft_msg(
+      w.fractionalizer_address.clone(),
+      w.nft_address.clone(),
+      "nft".to_string(),
+      &ReceiveMsg::Fractionalize {
+        owners: vec![Cw20Coin {
+          address: w.user_one.to_string(),
+          amount: Uint128::from(900u128),
+        }],
+        name: Some("name".to_string()),
+        symbol: Some("symbol".to_string()),
+        dutch_auction: None,
+        curator_fee_bps: None,
+        mintable: Some(true),
+        mint_cap: Some(Uint128::from(mint_cap)),
+        share_token: None,
+        backend: None,
+      },
+    )
+  };
+
+  // the cap cannot be below the initial supply
+  router
+    .execute(w.deployer_address.clone(), mintable(899))
+    .unwrap_err();
+  router
+    .execute(w.deployer_address.clone(), mintable(1000))
+    .unwrap();
+  let cw20_address = get_fractional_address(
+    router,
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+  );
+
+  let minter: Option<MinterResponse> = router
+    .wrap()
+    .query_wasm_smart(cw20_address.clone(), &Cw20QueryMsg::Minter {})
+    .unwrap();
+  assert_eq!(
+    minter,
+    Some(MinterResponse {
+      minter: w.fractionalizer_address.to_string(),
+      cap: Some(Uint128::from(1000u128)),
+    })
+  );
+  let vault: VaultInfo = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::VaultByCw20 { cw20: cw20_address },
+    )
+    .unwrap();
+  assert!(vault.mintable);
+
+  // supply stays fixed by default
+  let fixed = setup_vault(router, &w, "fixed");
+  let minter: Option<MinterResponse> = router
+    .wrap()
+    .query_wasm_smart(fixed.clone(), &Cw20QueryMsg::Minter {})
+    .unwrap();
+  assert_eq!(minter, None);
+  let vault: VaultInfo = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::VaultByCw20 {
+        cw20: fixed.to_string(),
+      },
+    )
+    .unwrap();
+  assert!(!vault.mintable);
+}
+
+#[test]
+fn test_share_token_params() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+    w.deployer_address.clone(),
+  );
+  let msg = |symbol: &str, decimals: u8| {
+    send_nft_msg(
+      w.fractionalizer_address.clone(),
+      w.nft_address.clone(),
+      "nft".to_string(),
+      &ReceiveMsg::Fractionalize {
+        owners: vec![Cw20Coin {
+          address: w.user_one.to_string(),
+          amount: Uint128::from(100u128),
+        }],
+        name: Some("name".to_string()),
+        symbol: Some(symbol.to_string()),
+        dutch_auction: None,
+        curator_fee_bps: None,
+        mintable: None,
+        mint_cap: None,
+        share_token: Some(ShareTokenParams {
+          decimals: Some(decimals),
+          marketing: Some(ShareTokenMarketing {
+            project: Some("project".to_string()),
+            description: Some("description".to_string()),
+            logo: None,
+          }),
+          label: Some("shares".to_string()),
+          admin: Some(true),
+        }),
+        backend: None,
+      },
+    )
+  };
+
+  let err = router
+    .execute(w.deployer_address.clone(), msg("s1", 8))
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::InvalidSymbol {}
+  );
+  let err = router
+    .execute(w.deployer_address.clone(), msg("symbol", 19))
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::InvalidDecimals {}
+  );
+
+  router
+    .execute(w.deployer_address.clone(), msg("symbol", 8))
+    .unwrap();
+  let cw20_address = Addr::unchecked(get_fractional_address(
+    router,
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+  ));
+
+  let info: TokenInfoResponse = router
+    .wrap()
+    .query_wasm_smart(cw20_address.clone(), &Cw20QueryMsg::TokenInfo {})
+    .unwrap();
+  assert_eq!(info.decimals, 8);
+  let marketing: MarketingInfoResponse = router
+    .wrap()
+    .query_wasm_smart(cw20_address.clone(), &Cw20QueryMsg::MarketingInfo {})
+    .unwrap();
+  assert_eq!(marketing.project, Some("project".to_string()));
+  assert_eq!(marketing.description, Some("description".to_string()));
+  assert_eq!(marketing.marketing, Some(w.deployer_address.clone()));
+
+  let contract = router.contract_data(&cw20_address).unwrap();
+  assert_eq!(contract.label, "shares");
+  assert_eq!(contract.admin, Some(w.fractionalizer_address.clone()));
+
+  // omitted names and marketing are taken from the collection
+  let mint_msg = Cw721ExecuteMsg::Mint::<_, Extension>(MintMsg::<Extension> {
+    token_id: "meta".to_string(),
+    owner: w.deployer_address.to_string(),
+    token_uri: Some("https://example.com/meta.json".to_string()),
+    extension: None,
+  });
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.nft_address.clone(),
+      &mint_msg,
+      &[],
+    )
+    .unwrap();
+  let msg = send_nft_msg(
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "meta".to_string(),
+    &ReceiveMsg::Fractionalize {
+      owners: vec![Cw20Coin {
+        address: w.user_one.to_string(),
+        amount: Uint128::from(100u128),
+      }],
+      name: None,
+      symbol: None,
+      dutch_auction: None,
+      curator_fee_bps: None,
+      mintable: None,
+      mint_cap: None,
+      share_token: None,
+      backend: None,
+    },
+  );
+  router.execute(w.deployer_address.clone(), msg).unwrap();
+  let cw20_address = get_fractional_address(
+    router,
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "meta".to_string(),
+  );
+
+  let info: TokenInfoResponse = router
+    .wrap()
+    .query_wasm_smart(cw20_address.clone(), &Cw20QueryMsg::TokenInfo {})
+    .unwrap();
+  assert_eq!(info.name, "Mock NFT #meta");
+  assert_eq!(info.symbol, "fMOCK");
+  assert_eq!(info.decimals, 6);
+  let marketing: MarketingInfoResponse = router
+    .wrap()
+    .query_wasm_smart(cw20_address, &Cw20QueryMsg::MarketingInfo {})
+    .unwrap();
+  assert_eq!(
+    marketing.logo,
+    Some(LogoInfo::Url("https://example.com/meta.json".to_string()))
+  );
+  assert_eq!(marketing.marketing, Some(w.deployer_address.clone()));
+}
+
+#[test]
+fn test_governance() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  let cw20_address = setup_vault(router, &w, "nft");
+  let propose = |action: ProposalAction| ExecuteMsg::Propose {
+    cw20: cw20_address.to_string(),
+    action,
+  };
+  let sale = ProposalAction::ApproveSale {
+    buyer: w.user_one.to_string(),
+    price: Uint128::from(100u128),
+  };
+
+  // only locked shares count as voting power
+  let err = router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &propose(sale.clone()),
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NoVotingPower {}
+  );
+  lock_shares(
+    router,
+    w.user_two.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    2,
+  )
+  .unwrap();
+  assert_eq!(
+    token_balance(router, cw20_address.to_string(), w.user_two.to_string()),
+    Uint128::zero()
+  );
+
+  let transfer = to_binary(&Cw721ExecuteMsg::<Extension, Empty>::TransferNft {
+    recipient: w.user_two.to_string(),
+    token_id: "nft".to_string(),
+  })
+  .unwrap();
+  let err = router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &propose(ProposalAction::NftAction { msg: transfer }),
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::ForbiddenNftAction {}
+  );
+  // messages that are not plain cw721 ones, or that touch other vaults' NFTs, are refused too
+  let unknown = Binary::from(
+    br#"{"transfer_nft":{"recipient":"user_two","token_id":"nft","memo":""}}"#.to_vec(),
+  );
+  let other_vault = to_binary(&Cw721ExecuteMsg::<Extension, Empty>::Revoke {
+    spender: w.user_two.to_string(),
+    token_id: "other".to_string(),
+  })
+  .unwrap();
+  for msg in [unknown, other_vault] {
+    let err = router
+      .execute_contract(
+        w.user_two.clone(),
+        w.fractionalizer_address.clone(),
+        &propose(ProposalAction::NftAction { msg }),
+        &[],
+      )
+      .unwrap_err();
+    assert_eq!(
+      err.downcast::<ContractError>().unwrap(),
+      ContractError::ForbiddenNftAction {}
+    );
+  }
+  let err = router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &propose(ProposalAction::MintShares {
+        recipient: w.user_two.to_string(),
+        amount: Uint128::from(1u128),
+      }),
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NotMintable {}
+  );
+
+  router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &propose(sale.clone()),
+      &[],
+    )
+    .unwrap();
+  let vote = ExecuteMsg::Vote {
+    proposal_id: 1,
+    vote: VoteOption::Yes,
+  };
+  router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &vote,
+      &[],
+    )
+    .unwrap();
+  let err = router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &vote,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::AlreadyVoted {}
+  );
+
+  let execute = ExecuteMsg::Execute { proposal_id: 1 };
+  let err = router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &execute,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::VotingNotEnded {}
+  );
+
+  // shares that voted stay locked until the proposal ends
+  let unlock = ExecuteMsg::Unlock {
+    cw20: cw20_address.to_string(),
+    amount: Uint128::from(2u128),
+  };
+  let err = router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &unlock,
+      &[],
+    )
+    .unwrap_err();
+  let lock: LockResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::Lock {
+        cw20: cw20_address.to_string(),
+        address: w.user_two.to_string(),
+      },
+    )
+    .unwrap();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::SharesLocked {
+      unlocks_at: lock.unlocks_at
+    }
+  );
+
+  router.update_block(|block| block.time = block.time.plus_seconds(VOTING_PERIOD));
+  router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &execute,
+      &[],
+    )
+    .unwrap();
+  let res: ProposalsResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::Proposals {
+        cw20: cw20_address.to_string(),
+        start_after: None,
+        limit: None,
+      },
+    )
+    .unwrap();
+  assert_eq!(
+    res.proposals,
+    vec![ProposalResponse {
+      id: 1,
+      cw20: cw20_address.to_string(),
+      proposer: w.user_two.to_string(),
+      action: sale,
+      yes_votes: Uint128::from(2u128),
+      no_votes: Uint128::zero(),
+      abstain_votes: Uint128::zero(),
+      end_time: lock.unlocks_at,
+      status: ProposalStatus::Executed,
+    }]
+  );
+  let res: Option<SaleResponse> = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::Sale {
+        cw20: cw20_address.to_string(),
+      },
+    )
+    .unwrap();
+  assert_eq!(
+    res,
+    Some(SaleResponse {
+      buyer: w.user_one.to_string(),
+      price: Uint128::from(100u128),
+    })
+  );
+
+  // a proposal still open when the NFT is sold is rejected once voting ends
+  router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &propose(ProposalAction::SetReservePrice {
+        price: Uint128::from(1000u128),
+      }),
+      &[],
+    )
+    .unwrap();
+  router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::Vote {
+        proposal_id: 2,
+        vote: VoteOption::Yes,
+      },
+      &[],
+    )
+    .unwrap();
+
+  // only the approved buyer can take the NFT, at the approved price
+  let accept = ExecuteMsg::AcceptSale {
+    cw20: cw20_address.to_string(),
+  };
+  fund(router, w.user_one.clone(), 100);
+  fund(router, w.user_two.clone(), 100);
+  let err = router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &accept,
+      &coins(100, DENOM),
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::Unauthorized {}
+  );
+  router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &accept,
+      &coins(100, DENOM),
+    )
+    .unwrap();
+  assert_eq!(
+    nft_owner_of(router, w.nft_address.to_string(), "nft".to_string()),
+    w.user_one.to_string()
+  );
+
+  router.update_block(|block| block.time = block.time.plus_seconds(VOTING_PERIOD));
+  router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::Execute { proposal_id: 2 },
+      &[],
+    )
+    .unwrap();
+  let res: ProposalResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::Proposal { proposal_id: 2 },
+    )
+    .unwrap();
+  assert_eq!(res.status, ProposalStatus::Rejected);
+
+  // locked shares come back once voting has ended, even after the sale
+  router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &unlock,
+      &[],
+    )
+    .unwrap();
+  assert_eq!(
+    token_balance(router, cw20_address.to_string(), w.user_two.to_string()),
+    Uint128::from(2u128)
+  );
+}
+
+#[test]
+fn test_token_factory_vault() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+    w.deployer_address.clone(),
+  );
+  let fractionalize_native = |curator_fee_bps: Option<u64>| {
+    send_nft_msg(
+      w.fractionalizer_address.clone(),
+      w.nft_address.clone(),
+      "nft".to_string(),
+      &ReceiveMsg::Fractionalize {
+        owners: vec![
+          Cw20Coin {
+            address: w.user_one.to_string(),
+            amount: Uint128::from(60u128),
+          },
+          Cw20Coin {
+            address: w.user_two.to_string(),
+            amount: Uint128::from(40u128),
+          },
+        ],
+        name: None,
+        symbol: None,
+        dutch_auction: None,
+        curator_fee_bps,
+        mintable: None,
+        mint_cap: None,
+        share_token: None,
+        backend: Some(ShareBackend::TokenFactory),
+      },
+    )
+  };
+
+  // curator fees are minted through a cw20 share token
+  let err = router
+    .execute(w.deployer_address.clone(), fractionalize_native(Some(100)))
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::UnsupportedByBackend {}
+  );
+
+  // the denom creation fee comes out of the creator's prepaid fees, not the contract's balance
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::UpdateConfig(UpdateConfigMsg {
+        owner: None,
+        cw20_code_id: None,
+        buyout_denom: None,
+        auction_duration: None,
+        shotgun_duration: None,
+        fees: None,
+        max_curator_fee_bps: None,
+        governance: None,
+        supply_limits: None,
+        denom_creation_fee: Some(coin(10, DENOM)),
+      }),
+      &[],
+    )
+    .unwrap();
+  let err = router
+    .execute(w.deployer_address.clone(), fractionalize_native(None))
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::FeeNotPaid {
+      fee: coin(10, DENOM)
+    }
+  );
+  fund(router, w.deployer_address.clone(), 10);
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::PayFee {},
+      &coins(10, DENOM),
+    )
+    .unwrap();
+
+  router
+    .execute(w.deployer_address.clone(), fractionalize_native(None))
+    .unwrap();
+  let res: FeeCreditResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::FeeCredit {
+        address: w.deployer_address.to_string(),
+      },
+    )
+    .unwrap();
+  assert!(res.credit.is_empty());
+  let denom = format!("factory/{}/vault1", w.fractionalizer_address);
+  assert_eq!(
+    get_fractional_address(
+      router,
+      w.fractionalizer_address.clone(),
+      w.nft_address.clone(),
+      "nft".to_string(),
+    ),
+    denom
+  );
+  let balance = router.wrap().query_balance(&w.user_one, &denom).unwrap();
+  assert_eq!(balance.amount, Uint128::from(60u128));
+  let res: VaultsResponse = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::ListVaults {
+        start_after: None,
+        limit: None,
+      },
+    )
+    .unwrap();
+  assert_eq!(res.vaults[0].cw20, denom);
+  assert_eq!(res.vaults[0].backend, ShareBackend::TokenFactory);
+  let res: VaultInfo = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::VaultByCw20 {
+        cw20: denom.clone(),
+      },
+    )
+    .unwrap();
+  assert_eq!(res.cw20, denom);
+
+  // unfractionalizing takes the whole supply, attached as funds
+  router
+    .send_tokens(w.user_two.clone(), w.user_one.clone(), &coins(40, &denom))
+    .unwrap();
+  let unfractionalize = ExecuteMsg::Unfractionalize {
+    recipient: w.user_one.to_string(),
+  };
+  let err = router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &unfractionalize,
+      &coins(99, &denom),
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::InsufficientFunds {}
+  );
+  fund(router, w.user_one.clone(), 100);
+  let err = router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &unfractionalize,
+      &coins(100, DENOM),
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::NotFractionalized {}
+  );
+
+  router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &unfractionalize,
+      &coins(100, &denom),
+    )
+    .unwrap();
+  assert_eq!(
+    nft_owner_of(router, w.nft_address.to_string(), "nft".to_string()),
+    w.user_one.to_string()
+  );
+  let supply = router.wrap().query_balance(&w.user_one, &denom).unwrap();
+  assert_eq!(supply.amount, Uint128::zero());
+  let held = router
+    .wrap()
+    .query_balance(&w.fractionalizer_address, &denom)
+    .unwrap();
+  assert_eq!(held.amount, Uint128::zero());
+}
+
+#[test]
+fn test_pause() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  let cw20_address = setup_vault(router, &w, "nft");
+  let pause = |paused: bool| ExecuteMsg::Pause {
+    fractionalize: paused,
+    unfractionalize: paused,
+    auctions: paused,
+  };
+
+  let err = router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &pause(true),
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::Unauthorized {}
+  );
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &pause(true),
+      &[],
+    )
+    .unwrap();
+  let res: PauseState = router
+    .wrap()
+    .query_wasm_smart(w.fractionalizer_address.clone(), &QueryMsg::Paused {})
+    .unwrap();
+  assert_eq!(
+    res,
+    PauseState {
+      fractionalize: true,
+      unfractionalize: true,
+      auctions: true,
+    }
+  );
+
+  // the NFT stays with its owner
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    "nft2".to_string(),
+    w.deployer_address.clone(),
+  );
+  let owners = vec![Cw20Coin {
+    address: w.user_one.to_string(),
+    amount: Uint128::from(1u128),
+  }];
+  let msg = fractionalize_msg(
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "nft2".to_string(),
+    owners.clone(),
+  );
+  let err = router.execute(w.deployer_address.clone(), msg).unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::Paused {}
+  );
+  assert_eq!(
+    nft_owner_of(router, w.nft_address.to_string(), "nft2".to_string()),
+    w.deployer_address.to_string()
+  );
+
+  fund(router, w.user_one.clone(), 1000);
+  let err = bid(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.to_string(),
+    100,
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::Paused {}
+  );
+  let err = redeem(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    Uint128::from(1u128),
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::Paused {}
+  );
+
+  // the whole supply can always take the NFT out
+  token_transfer(
+    router,
+    w.user_two.clone(),
+    cw20_address.clone(),
+    Uint128::from(2u128),
+    w.user_one.clone(),
+  );
+  unfractionalize(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address,
+    Uint128::from(3u128),
+  )
+  .unwrap();
+  assert_eq!(
+    nft_owner_of(router, w.nft_address.to_string(), "nft".to_string()),
+    w.user_one.to_string()
+  );
+
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &pause(false),
+      &[],
+    )
+    .unwrap();
+  fractionalize(
+    router,
+    w.deployer_address.clone(),
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "nft2".to_string(),
+    owners,
+  );
+}
+
+#[test]
+fn test_pause_holds_auctions() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  fund(router, w.user_one.clone(), 1000);
+  fund(router, w.user_two.clone(), 1000);
+  let buyer = Addr::unchecked("buyer");
+  fund(router, buyer.clone(), 1000);
+  let cw20_address = setup_vault(router, &w, "nft");
+  let dutch_address = setup_dutch_auction_vault(
+    router,
+    &w,
+    "nft2",
+    DutchAuctionParams {
+      start_price: Uint128::from(1000u128),
+      floor_price: Uint128::from(200u128),
+      duration: 800,
+      decay: PriceDecay::Linear,
+    },
+  );
+  let pause_auctions = |auctions: bool| ExecuteMsg::Pause {
+    fractionalize: false,
+    unfractionalize: false,
+    auctions,
+  };
+  let shotgun = |router: &mut App| -> ShotgunResponse {
+    let res: Option<ShotgunResponse> = router
+      .wrap()
+      .query_wasm_smart(
+        w.fractionalizer_address.clone(),
+        &QueryMsg::Shotgun {
+          cw20: cw20_address.to_string(),
+        },
+      )
+      .unwrap();
+    res.unwrap()
+  };
+
+  start_shotgun(
+    router,
+    w.user_one.clone(),
+    w.fractionalizer_address.clone(),
+    cw20_address.clone(),
+    1,
+    50,
+    100,
+  )
+  .unwrap();
+  let deadline = shotgun(router).deadline;
+
+  // user_two can't counter while paused, so the offer must not run out in the meantime
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &pause_auctions(true),
+      &[],
+    )
+    .unwrap();
+  router.update_block(|block| block.time = block.time.plus_seconds(SHOTGUN_DURATION));
+  let msg = ExecuteMsg::CounterShotgun {
+    cw20: cw20_address.to_string(),
+  };
+  let err = router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &coins(50, DENOM),
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::Paused {}
+  );
+  assert_eq!(
+    shotgun(router).deadline,
+    deadline.plus_seconds(SHOTGUN_DURATION)
+  );
+  assert_eq!(
+    dutch_auction_price(router, &w, &dutch_address),
+    Uint128::from(1000u128)
+  );
+
+  router
+    .execute_contract(
+      w.deployer_address.clone(),
+      w.fractionalizer_address.clone(),
+      &pause_auctions(false),
+      &[],
+    )
+    .unwrap();
+  let complete = ExecuteMsg::CompleteShotgun {
+    cw20: cw20_address.to_string(),
+  };
+  let err = router
+    .execute_contract(
+      w.user_one.clone(),
+      w.fractionalizer_address.clone(),
+      &complete,
+      &[],
+    )
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::ShotgunNotExpired {}
+  );
+  router
+    .execute_contract(
+      w.user_two.clone(),
+      w.fractionalizer_address.clone(),
+      &msg,
+      &coins(50, DENOM),
+    )
+    .unwrap();
+
+  // the Dutch auction picks up its decay where the pause stopped it
+  router.update_block(|block| block.time = block.time.plus_seconds(400));
+  assert_eq!(
+    dutch_auction_price(router, &w, &dutch_address),
+    Uint128::from(600u128)
+  );
+  router
+    .execute_contract(
+      buyer.clone(),
+      w.fractionalizer_address.clone(),
+      &ExecuteMsg::BuyNow {
+        cw20: dutch_address.to_string(),
+      },
+      &coins(600, DENOM),
+    )
+    .unwrap();
+  assert_eq!(
+    nft_owner_of(router, w.nft_address.to_string(), "nft2".to_string()),
+    buyer.to_string()
+  );
+}
+
+#[test]
+fn test_collection_lists() {
+  let router = &mut mock_app();
+  let w = setup(router);
+  let execute = |router: &mut App, sender: &Addr, msg: &ExecuteMsg| {
+    router
+      .execute_contract(sender.clone(), w.fractionalizer_address.clone(), msg, &[])
+      .map_err(|err| err.downcast::<ContractError>().unwrap())
+  };
+  let collections = |router: &mut App, msg: &QueryMsg| {
+    router
+      .wrap()
+      .query_wasm_smart::<CollectionsResponse>(w.fractionalizer_address.clone(), msg)
+      .unwrap()
+      .collections
+  };
+  let allowlist = ExecuteMsg::SetCollectionMode {
+    mode: CollectionMode::Allowlist,
+  };
+
+  assert_eq!(
+    execute(router, &w.user_one, &allowlist).unwrap_err(),
+    ContractError::Unauthorized {}
+  );
+  execute(router, &w.deployer_address, &allowlist).unwrap();
+  let mode: CollectionMode = router
+    .wrap()
+    .query_wasm_smart(
+      w.fractionalizer_address.clone(),
+      &QueryMsg::CollectionMode {},
+    )
+    .unwrap();
+  assert_eq!(mode, CollectionMode::Allowlist);
+
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+    w.deployer_address.clone(),
+  );
+  let msg = fractionalize_msg(
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "nft".to_string(),
+    vec![Cw20Coin {
+      address: w.user_one.to_string(),
+      amount: Uint128::from(1u128),
+    }],
+  );
+  let err = router
+    .execute(w.deployer_address.clone(), msg.clone())
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::CollectionNotAllowed {
+      collection: w.nft_address.to_string()
+    }
+  );
+  assert_eq!(
+    nft_owner_of(router, w.nft_address.to_string(), "nft".to_string()),
+    w.deployer_address.to_string()
+  );
+
+  let update = ExecuteMsg::UpdateAllowlist {
+    add: vec![w.nft_address.to_string()],
+    remove: vec![],
+  };
+  assert_eq!(
+    execute(router, &w.user_one, &update).unwrap_err(),
+    ContractError::Unauthorized {}
+  );
+  execute(router, &w.deployer_address, &update).unwrap();
+  assert_eq!(
+    collections(
+      router,
+      &QueryMsg::Allowlist {
+        start_after: None,
+        limit: None,
+      }
+    ),
+    vec![w.nft_address.to_string()]
+  );
+  router.execute(w.deployer_address.clone(), msg).unwrap();
+
+  // denylisted collections are rejected, everything else is accepted
+  execute(
+    router,
+    &w.deployer_address,
+    &ExecuteMsg::SetCollectionMode {
+      mode: CollectionMode::Denylist,
+    },
+  )
+  .unwrap();
+  // a basket opened before its collection was denied cannot be finalized either
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    "basket".to_string(),
+    w.deployer_address.clone(),
+  );
+  execute(
+    router,
+    &w.deployer_address,
+    &ExecuteMsg::OpenBasket {
+      collection: w.nft_address.to_string(),
+    },
+  )
+  .unwrap();
+  let deposit = send_nft_msg(
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "basket".to_string(),
+    &ReceiveMsg::DepositToBasket { basket_id: 1 },
+  );
+  router.execute(w.deployer_address.clone(), deposit).unwrap();
+  execute(
+    router,
+    &w.deployer_address,
+    &ExecuteMsg::UpdateDenylist {
+      add: vec![
+        "collection_b".to_string(),
+        "collection_a".to_string(),
+        w.nft_address.to_string(),
+      ],
+      remove: vec![],
+    },
+  )
+  .unwrap();
+  let finalize = ExecuteMsg::FinalizeBasket(FinalizeBasketMsg {
+    basket_id: 1,
+    owners: vec![Cw20Coin {
+      address: w.user_one.to_string(),
+      amount: Uint128::from(1u128),
+    }],
+    name: Some("basket".to_string()),
+    symbol: Some("BSKT".to_string()),
+    dutch_auction: None,
+    curator_fee_bps: None,
+    mintable: None,
+    mint_cap: None,
+    share_token: None,
+    backend: None,
+  });
+  assert_eq!(
+    execute(router, &w.deployer_address, &finalize).unwrap_err(),
+    ContractError::CollectionNotAllowed {
+      collection: w.nft_address.to_string()
+    }
+  );
+  mint_nft(
+    router,
+    w.deployer_address.clone(),
+    w.nft_address.clone(),
+    "nft2".to_string(),
+    w.deployer_address.clone(),
+  );
+  let msg = fractionalize_msg(
+    w.fractionalizer_address.clone(),
+    w.nft_address.clone(),
+    "nft2".to_string(),
+    vec![Cw20Coin {
+      address: w.user_one.to_string(),
+      amount: Uint128::from(1u128),
+    }],
+  );
+  let err = router
+    .execute(w.deployer_address.clone(), msg.clone())
+    .unwrap_err();
+  assert_eq!(
+    err.downcast::<ContractError>().unwrap(),
+    ContractError::CollectionNotAllowed {
+      collection: w.nft_address.to_string()
+    }
+  );
+
+  assert_eq!(
+    collections(
+      router,
+      &QueryMsg::Denylist {
+        start_after: None,
+        limit: Some(2),
+      }
+    ),
+    vec!["collection_a".to_string(), "collection_b".to_string()]
+  );
+  assert_eq!(
+    collections(
+      router,
+      &QueryMsg::Denylist {
+        start_after: Some("collection_b".to_string()),
+        limit: None,
+      }
+    ),
+    vec![w.nft_address.to_string()]
+  );
+
+  execute(
+    router,
+    &w.deployer_address,
+    &ExecuteMsg::UpdateDenylist {
+      add: vec![],
+      remove: vec![w.nft_address.to_string()],
+    },
+  )
+  .unwrap();
+  router.execute(w.deployer_address.clone(), msg).unwrap();
+}
@@ -3,7 +3,6 @@ pub mod msg;
 pub mod state;
 
 mod error;
-mod response;
 
 #[cfg(test)]
 mod integration_tests;
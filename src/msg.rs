@@ -1,36 +1,599 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cw20::{Cw20Coin, Cw20ReceiveMsg};
+use cosmwasm_std::{Binary, Coin, CustomMsg, Timestamp, Uint128};
+use cw20::{Cw20Coin, Cw20ReceiveMsg, Logo};
 use cw721::Cw721ReceiveMsg;
 
 #[cw_serde]
-pub struct InstantiateMsg {}
+pub struct MigrateMsg {
+  /// Configuration to start from when migrating from 0.0.1, which stored none. `owner` is
+  /// required.
+  pub config: Option<InstantiateMsg>,
+}
+
+#[cw_serde]
+pub struct InstantiateMsg {
+  /// Defaults to the instantiator when omitted
+  pub owner: Option<String>,
+  /// Code ID of the cw20-base contract used for share tokens
+  pub cw20_code_id: u64,
+  /// Native denom that buyouts are paid in
+  pub buyout_denom: String,
+  /// Seconds a buyout auction runs for once the reserve price has been met
+  pub auction_duration: u64,
+  /// Seconds share holders have to counter a shotgun offer
+  pub shotgun_duration: u64,
+  /// Protocol fees, none are charged when omitted
+  pub fees: Option<FeeConfig>,
+  /// Highest annual curator fee, in basis points of the share supply, a vault can be created with
+  pub max_curator_fee_bps: u64,
+  pub governance: GovernanceConfig,
+  /// Bounds on the initial share supply, unbounded when omitted
+  pub supply_limits: Option<SupplyLimits>,
+  /// Tokenfactory's denom creation fee, charged from the creator's prepaid fees for every
+  /// `token_factory` vault. The chain takes it from the contract's balance, so it must match the
+  /// chain's fee. Nothing is charged when omitted.
+  pub denom_creation_fee: Option<Coin>,
+}
+
+/// Which collections can be fractionalized
+#[cw_serde]
+pub enum CollectionMode {
+  Open,
+  /// Only allowlisted collections
+  Allowlist,
+  /// Every collection that is not denylisted
+  Denylist,
+}
+
+/// Flows the owner has paused. Unfractionalizing with the whole share supply is never paused, so
+/// NFTs can always be taken out of their vaults.
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseState {
+  /// New vaults, including finalized baskets
+  pub fractionalize: bool,
+  /// Redeeming shares for a part of the vault's proceeds and assets
+  pub unfractionalize: bool,
+  /// Reserve price auctions, shotgun offers, Dutch auctions and approved sales. Running auctions
+  /// are pushed back by the time spent paused, so no deadline passes and no price decays while
+  /// bids and counters are blocked.
+  pub auctions: bool,
+}
+
+/// Bounds on the initial share supply, protocol fee shares included
+#[cw_serde]
+#[derive(Default)]
+pub struct SupplyLimits {
+  pub min: Option<Uint128>,
+  pub max: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct GovernanceConfig {
+  /// Seconds a proposal is open for voting
+  pub voting_period: u64,
+  /// Basis points of the share supply that must take part for a proposal to pass
+  pub quorum_bps: u64,
+  /// Basis points of the yes and no votes that must be yes for a proposal to pass
+  pub threshold_bps: u64,
+  /// Basis points of the share supply that must have voted a reserve price before an auction
+  pub reserve_quorum_bps: u64,
+}
+
+#[cw_serde]
+pub struct FeeConfig {
+  pub recipient: String,
+  /// Basis points of the minted supply issued to the recipient on top of the owners' balances
+  pub fractionalize_bps: u64,
+  /// Native fee for every vault, prepaid with `PayFee`
+  pub flat_fee: Option<Coin>,
+  /// Basis points of buyout proceeds sent to the recipient
+  pub buyout_bps: u64,
+}
+
+#[cw_serde]
+pub struct UpdateConfigMsg {
+  pub owner: Option<String>,
+  pub cw20_code_id: Option<u64>,
+  pub buyout_denom: Option<String>,
+  pub auction_duration: Option<u64>,
+  pub shotgun_duration: Option<u64>,
+  pub fees: Option<FeeConfig>,
+  pub max_curator_fee_bps: Option<u64>,
+  pub governance: Option<GovernanceConfig>,
+  pub supply_limits: Option<SupplyLimits>,
+  /// A zero amount removes the fee
+  pub denom_creation_fee: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct FinalizeBasketMsg {
+  pub basket_id: u64,
+  pub owners: Vec<Cw20Coin>,
+  /// Derived from the collection when omitted
+  pub name: Option<String>,
+  /// Derived from the collection when omitted
+  pub symbol: Option<String>,
+  pub dutch_auction: Option<DutchAuctionParams>,
+  pub curator_fee_bps: Option<u64>,
+  pub mintable: Option<bool>,
+  pub mint_cap: Option<Uint128>,
+  pub share_token: Option<ShareTokenParams>,
+  pub backend: Option<ShareBackend>,
+}
 
 #[cw_serde]
 pub enum ExecuteMsg {
   Receive(Cw20ReceiveMsg),
   ReceiveNft(Cw721ReceiveMsg),
+  UpdateConfig(UpdateConfigMsg),
+  /// Returns locked shares once every proposal voted on with them has closed
+  Unlock {
+    cw20: String,
+    amount: Uint128,
+  },
+  /// Votes the price a share holder is willing to sell the NFT at, weighted by their locked shares
+  SetReservePrice {
+    cw20: String,
+    price: Uint128,
+  },
+  /// Bids on the NFT with the attached funds, starting an auction when none is running
+  Bid {
+    cw20: String,
+  },
+  /// Sends the NFT to the highest bidder once the auction has ended
+  SettleAuction {
+    cw20: String,
+  },
+  /// Offers to buy all other shares at `price_per_share`, escrowing the attached funds and the
+  /// sender's whole balance, which must have been approved to this contract beforehand
+  StartShotgun {
+    cw20: String,
+    price_per_share: Uint128,
+  },
+  /// Buys the initiator's escrowed shares at the offered price, cancelling the offer
+  CounterShotgun {
+    cw20: String,
+  },
+  /// Sends the NFT to the initiator once the offer was not countered in time
+  CompleteShotgun {
+    cw20: String,
+  },
+  /// Buys the NFT at the current Dutch auction price, refunding any excess funds
+  BuyNow {
+    cw20: String,
+  },
+  /// Adds the attached funds to the assets share holders can redeem
+  Deposit {
+    cw20: String,
+  },
+  /// Opens a draft basket vault that NFTs of `collection` can be sent to with `DepositToBasket`
+  OpenBasket {
+    collection: String,
+  },
+  /// Mints a single share token for every NFT deposited into the basket
+  FinalizeBasket(FinalizeBasketMsg),
+  /// Returns the deposited NFTs to the creator and drops the draft
+  CancelBasket {
+    basket_id: u64,
+  },
+  /// Prepays the flat fee and denom creation fee for the sender's next vaults, since NFTs cannot
+  /// be sent with funds
+  PayFee {},
+  /// Refunds the sender's prepaid fees that no vault has used yet
+  WithdrawFeeCredit {},
+  /// Retries the transfer of a deposited cw20 whose payout to the sender failed
+  ClaimAsset {
+    token: String,
+  },
+  /// Mints the curator fee accrued since the last claim to the curator
+  ClaimCuratorFee {
+    cw20: String,
+  },
+  /// Opens a proposal for the holders of `cw20`, the sender needs locked shares
+  Propose {
+    cw20: String,
+    action: ProposalAction,
+  },
+  /// Votes on an open proposal with all of the sender's locked shares
+  Vote {
+    proposal_id: u64,
+    vote: VoteOption,
+  },
+  /// Closes a proposal once voting has ended, running its action if it passed
+  Execute {
+    proposal_id: u64,
+  },
+  /// Buys the NFT at the price approved by a passed `ApproveSale` proposal
+  AcceptSale {
+    cw20: String,
+  },
+  /// Burns the attached whole supply of a tokenfactory share denom for the NFT
+  Unfractionalize {
+    recipient: String,
+  },
+  /// Sets which flows are paused, owner only
+  Pause {
+    fractionalize: bool,
+    unfractionalize: bool,
+    auctions: bool,
+  },
+  /// Sets which collections can be fractionalized, owner only
+  SetCollectionMode {
+    mode: CollectionMode,
+  },
+  /// Owner only
+  UpdateAllowlist {
+    add: Vec<String>,
+    remove: Vec<String>,
+  },
+  /// Owner only
+  UpdateDenylist {
+    add: Vec<String>,
+    remove: Vec<String>,
+  },
+}
+
+#[cw_serde]
+pub enum ProposalAction {
+  /// Overrides the reserve price voted with `SetReservePrice`
+  SetReservePrice { price: Uint128 },
+  /// Hands the curator role, and the fee accruing from now on, to another address
+  UpdateCurator { curator: String },
+  /// Lets `buyer` take the NFT for `price` with `AcceptSale`
+  ApproveSale { buyer: String, price: Uint128 },
+  /// Executes `msg` on the NFT's collection, limited to cw721 `revoke` for the vault's NFTs and
+  /// `revoke_all`
+  NftAction { msg: Binary },
+  /// Issues new shares, the vault must be mintable
+  MintShares { recipient: String, amount: Uint128 },
 }
 
 #[cw_serde]
+pub enum VoteOption {
+  Yes,
+  No,
+  Abstain,
+}
+
+#[cw_serde]
+pub enum ProposalStatus {
+  Open,
+  Executed,
+  Rejected,
+}
+
+#[cw_serde]
+pub enum PriceDecay {
+  Linear,
+  /// The premium over the floor price halves every `half_life` seconds
+  Exponential {
+    half_life: u64,
+  },
+}
+
+#[cw_serde]
+pub enum ShareBackend {
+  Cw20,
+  /// Native `factory/<contract>/vault<n>` denom, needs the tokenfactory module
+  TokenFactory,
+}
+
+/// Tokenfactory messages, dispatched as custom messages to the chain's bindings
+#[cw_serde]
+pub enum TokenFactoryMsg {
+  CreateDenom {
+    subdenom: String,
+  },
+  MintTokens {
+    denom: String,
+    amount: Uint128,
+    mint_to_address: String,
+  },
+  BurnTokens {
+    denom: String,
+    amount: Uint128,
+    burn_from_address: String,
+  },
+}
+
+impl CustomMsg for TokenFactoryMsg {}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ShareTokenParams {
+  /// Defaults to 6
+  pub decimals: Option<u8>,
+  pub marketing: Option<ShareTokenMarketing>,
+  /// Label of the share token contract, defaults to "fractionalize"
+  pub label: Option<String>,
+  /// Makes the fractionalizer admin of the share token so it can be migrated later
+  pub admin: Option<bool>,
+}
+
+/// Description and logo default to the NFT's metadata
+#[cw_serde]
+#[derive(Default)]
+pub struct ShareTokenMarketing {
+  pub project: Option<String>,
+  pub description: Option<String>,
+  pub logo: Option<Logo>,
+}
+
+#[cw_serde]
+pub struct DutchAuctionParams {
+  pub start_price: Uint128,
+  pub floor_price: Uint128,
+  /// Seconds for the price to decay from start to floor price
+  pub duration: u64,
+  pub decay: PriceDecay,
+}
+
+#[cw_serde]
+#[allow(clippy::large_enum_variant)]
 pub enum ReceiveMsg {
   Fractionalize {
     owners: Vec<Cw20Coin>,
-    name: String,
-    symbol: String,
+    /// Derived from the collection's name and the token ID when omitted
+    name: Option<String>,
+    /// Derived from the collection's symbol when omitted
+    symbol: Option<String>,
+    /// Lists the NFT for sale at a decaying price as soon as it is fractionalized
+    dutch_auction: Option<DutchAuctionParams>,
+    /// Annual fee, in basis points of the share supply, minted to the sender as curator
+    curator_fee_bps: Option<u64>,
+    /// Keeps the fractionalizer as minter of the share token so its supply can change later
+    mintable: Option<bool>,
+    /// Highest supply the share token can be minted up to, implies `mintable`
+    mint_cap: Option<Uint128>,
+    share_token: Option<ShareTokenParams>,
+    /// Defaults to a cw20 share token
+    backend: Option<ShareBackend>,
+  },
+  /// Adds the sent NFT to a draft basket opened by the sender
+  DepositToBasket {
+    basket_id: u64,
   },
   Unfractionalize {
     recipient: String,
   },
+  /// Locks the sent shares in the contract as voting power
+  Lock {},
+  /// Burns shares for their pro-rata part of every asset held by the vault, giving up any claim
+  /// on the NFT while it is still held
+  Redeem {
+    recipient: String,
+  },
+  /// Adds the sent tokens to the assets share holders of `cw20` can redeem
+  Deposit {
+    cw20: String,
+  },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
+  #[returns(ConfigResponse)]
+  Config {},
+  #[returns(Option<FeeConfig>)]
+  Fees {},
+  #[returns(FeeCreditResponse)]
+  FeeCredit { address: String },
+  #[returns(AssetClaimsResponse)]
+  AssetClaims { address: String },
+  /// Errors when the NFT is not fractionalized
   #[returns(GetCw20AddressResponse)]
   GetCw20Address { address: String, token_id: String },
+  #[returns(TryGetCw20AddressResponse)]
+  TryGetCw20Address { address: String, token_id: String },
+  #[returns(LockResponse)]
+  Lock { cw20: String, address: String },
+  #[returns(VaultsResponse)]
+  ListVaults {
+    start_after: Option<String>,
+    limit: Option<u32>,
+  },
+  #[returns(VaultsResponse)]
+  VaultsByCollection {
+    collection: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+  },
+  #[returns(VaultInfo)]
+  VaultByCw20 { cw20: String },
+  #[returns(VaultAssetsResponse)]
+  VaultAssets { cw20: String },
+  #[returns(Option<BasketResponse>)]
+  Basket { basket_id: u64 },
+  #[returns(Option<CuratorResponse>)]
+  Curator { cw20: String },
+  #[returns(ProposalResponse)]
+  Proposal { proposal_id: u64 },
+  #[returns(ProposalsResponse)]
+  Proposals {
+    cw20: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+  },
+  #[returns(Option<SaleResponse>)]
+  Sale { cw20: String },
+  #[returns(ReservePriceResponse)]
+  ReservePrice { cw20: String },
+  #[returns(PauseState)]
+  Paused {},
+  #[returns(CollectionMode)]
+  CollectionMode {},
+  #[returns(CollectionsResponse)]
+  Allowlist {
+    start_after: Option<String>,
+    limit: Option<u32>,
+  },
+  #[returns(CollectionsResponse)]
+  Denylist {
+    start_after: Option<String>,
+    limit: Option<u32>,
+  },
+  #[returns(Option<AuctionResponse>)]
+  Auction { cw20: String },
+  #[returns(Option<ShotgunResponse>)]
+  Shotgun { cw20: String },
+  #[returns(Option<DutchAuctionResponse>)]
+  DutchAuction { cw20: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+  pub owner: String,
+  pub cw20_code_id: u64,
+  pub buyout_denom: String,
+  pub auction_duration: u64,
+  pub shotgun_duration: u64,
+  pub max_curator_fee_bps: u64,
+  pub governance: GovernanceConfig,
+  pub supply_limits: SupplyLimits,
+  pub denom_creation_fee: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct FeeCreditResponse {
+  pub credit: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct AssetClaimsResponse {
+  pub claims: Vec<Cw20Coin>,
+}
+
+#[cw_serde]
+pub struct ReservePriceResponse {
+  /// Median of the voted prices weighted by locked shares, or the price set by a proposal
+  pub price: Option<Uint128>,
+  /// Locked shares behind the voted prices
+  pub voted_shares: Uint128,
+  /// Whether an auction can start at `price`
+  pub quorum_reached: bool,
+}
+
+#[cw_serde]
+pub struct AuctionResponse {
+  pub bidder: String,
+  pub amount: Uint128,
+  pub denom: String,
+  pub end_time: Timestamp,
+}
+
+#[cw_serde]
+pub struct LockResponse {
+  pub amount: Uint128,
+  /// End of the last proposal voted on with these shares
+  pub unlocks_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct ShotgunResponse {
+  pub initiator: String,
+  pub price_per_share: Uint128,
+  pub shares: Uint128,
+  pub escrow: Uint128,
+  pub denom: String,
+  pub deadline: Timestamp,
+}
+
+#[cw_serde]
+pub struct DutchAuctionResponse {
+  pub start_price: Uint128,
+  pub floor_price: Uint128,
+  pub start_time: Timestamp,
+  pub end_time: Timestamp,
+  pub decay: PriceDecay,
+  pub current_price: Uint128,
 }
 
 #[cw_serde]
 pub struct GetCw20AddressResponse {
   pub address: String,
 }
+
+#[cw_serde]
+pub struct TryGetCw20AddressResponse {
+  pub address: Option<String>,
+  pub is_fractionalized: bool,
+}
+
+#[cw_serde]
+pub enum VaultStatus {
+  Active,
+  Auction,
+  Shotgun,
+  Sold,
+  Unfractionalized,
+}
+
+#[cw_serde]
+pub struct VaultInfo {
+  pub collection: String,
+  pub token_ids: Vec<String>,
+  pub cw20: String,
+  pub creator: String,
+  pub created_at: Timestamp,
+  pub status: VaultStatus,
+  /// Whether the fractionalizer can mint new shares
+  pub mintable: bool,
+  pub backend: ShareBackend,
+}
+
+#[cw_serde]
+pub struct CollectionsResponse {
+  pub collections: Vec<String>,
+}
+
+#[cw_serde]
+pub struct VaultsResponse {
+  pub vaults: Vec<VaultInfo>,
+}
+
+#[cw_serde]
+pub struct CuratorResponse {
+  pub curator: String,
+  pub fee_bps: u64,
+  pub last_claim: Timestamp,
+  /// Shares that would be minted by claiming now
+  pub claimable: Uint128,
+}
+
+#[cw_serde]
+pub struct ProposalResponse {
+  pub id: u64,
+  pub cw20: String,
+  pub proposer: String,
+  pub action: ProposalAction,
+  pub yes_votes: Uint128,
+  pub no_votes: Uint128,
+  pub abstain_votes: Uint128,
+  pub end_time: Timestamp,
+  pub status: ProposalStatus,
+}
+
+#[cw_serde]
+pub struct ProposalsResponse {
+  pub proposals: Vec<ProposalResponse>,
+}
+
+#[cw_serde]
+pub struct SaleResponse {
+  pub buyer: String,
+  pub price: Uint128,
+}
+
+#[cw_serde]
+pub struct BasketResponse {
+  pub creator: String,
+  pub collection: String,
+  pub token_ids: Vec<String>,
+}
+
+#[cw_serde]
+pub struct VaultAssetsResponse {
+  pub native: Vec<Coin>,
+  pub cw20: Vec<Cw20Coin>,
+}
@@ -1,14 +1,439 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Coin, Empty, StdResult, Storage, Timestamp, Uint128};
+use cw20::Cw20CoinVerified;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+
+use crate::msg::{
+  CollectionMode, DutchAuctionParams, GovernanceConfig, PauseState, PriceDecay, ProposalAction,
+  ProposalStatus, ShareBackend, SupplyLimits, VaultStatus,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
-  pub last_nft_fractionalized: (Addr, String),
+  pub owner: Addr,
+  pub cw20_code_id: u64,
+  /// Native denom that buyouts are paid in
+  pub buyout_denom: String,
+  /// Seconds a buyout auction runs for once the reserve price has been met
+  pub auction_duration: u64,
+  /// Seconds share holders have to counter a shotgun offer
+  pub shotgun_duration: u64,
+  pub fees: Option<Fees>,
+  pub max_curator_fee_bps: u64,
+  pub governance: GovernanceConfig,
+  pub supply_limits: SupplyLimits,
+  /// Tokenfactory's denom creation fee, charged to the creators of native share vaults
+  #[serde(default)]
+  pub denom_creation_fee: Option<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Fees {
+  pub recipient: Addr,
+  pub fractionalize_bps: u64,
+  pub flat_fee: Option<Coin>,
+  pub buyout_bps: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Vault {
+  pub collection: Addr,
+  /// Every NFT of `collection` held by the vault, a single one unless it is a basket
+  pub token_ids: Vec<String>,
+  pub creator: Addr,
+  pub created_at: Timestamp,
+  pub status: VaultStatus,
+  /// Whether the fractionalizer is the share token's minter
+  pub mintable: bool,
+  pub backend: ShareBackend,
+  /// Code the cw20 share token was instantiated from, unknown for vaults created by 0.0.1
+  pub cw20_code_id: Option<u64>,
+}
+
+pub struct VaultIndexes<'a> {
+  pub collection: MultiIndex<'a, Addr, Vault, String>,
+}
+
+impl<'a> IndexList<Vault> for VaultIndexes<'a> {
+  fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Vault>> + '_> {
+    let v: Vec<&dyn Index<Vault>> = vec![&self.collection];
+    Box::new(v.into_iter())
+  }
+}
+
+/// Every vault ever created, keyed by share token
+pub fn vaults<'a>() -> IndexedMap<'a, &'a str, Vault, VaultIndexes<'a>> {
+  let indexes = VaultIndexes {
+    collection: MultiIndex::new(
+      |_pk, vault| vault.collection.clone(),
+      "vaults",
+      "vaults__collection",
+    ),
+  };
+  IndexedMap::new("vaults", indexes)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingVault {
+  pub collection: Addr,
+  pub token_ids: Vec<String>,
+  pub creator: Addr,
+  pub dutch_auction: Option<DutchAuctionParams>,
+  pub curator_fee_bps: u64,
+  pub mintable: bool,
+  pub mint_cap: Option<Uint128>,
+  pub backend: ShareBackend,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Curator {
+  pub address: Addr,
+  /// Annual fee in basis points of the share supply
+  pub fee_bps: u64,
+  pub last_claim: Timestamp,
+}
+
+impl Curator {
+  /// Shares accrued on `supply` since the last claim, without compounding
+  pub fn accrued(&self, supply: Uint128, time: Timestamp) -> Uint128 {
+    let elapsed = time.seconds().saturating_sub(self.last_claim.seconds());
+    supply.multiply_ratio(
+      u128::from(self.fee_bps) * u128::from(elapsed),
+      10_000u128 * u128::from(SECONDS_PER_YEAR),
+    )
+  }
+}
+
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// Curators of vaults created with a curator fee, keyed by share token
+pub const CURATORS: Map<&str, Curator> = Map::new("curators");
+
+/// Weighted by the voter's locked shares at the time the reserve price is read
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReservePriceVote {
+  pub price: Uint128,
 }
 
-pub const CW20_NFT: Map<String, (Addr, String)> = Map::new("CW20_NFT");
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Auction {
+  pub bidder: Addr,
+  pub amount: Uint128,
+  /// Buyout denom when the auction started, kept for every later bid, refund and the proceeds
+  pub denom: String,
+  pub end_time: Timestamp,
+  /// Auction pause time already added to `end_time`
+  #[serde(default)]
+  pub paused_seconds: u64,
+}
+
+// Pushes `time` back by the pause clock's `total` seconds not yet `applied` to it
+fn resume(time: &mut Timestamp, applied: &mut u64, total: u64) {
+  *time = time.plus_seconds(total.saturating_sub(*applied));
+  *applied = total;
+}
+
+impl Auction {
+  /// Pushes the end time back by the time auctions were paused since it was last set
+  pub fn resume(&mut self, paused_seconds: u64) {
+    resume(&mut self.end_time, &mut self.paused_seconds, paused_seconds);
+  }
+}
+
+/// Share token of each NFT as stored by 0.0.1, moved into `vaults` by the 0.1.0 migration
+pub const LEGACY_CW20_NFT: Map<String, (Addr, String)> = Map::new("CW20_NFT");
+
+/// Share token of every NFT currently held in a vault
 pub const NFT_CW20: Map<(Addr, String), String> = Map::new("NFT_CW20");
+
+/// Supply of each tokenfactory share denom, which only this contract mints. Kept at zero once the
+/// vault closes.
+pub const SHARE_DENOMS: Map<&str, Uint128> = Map::new("share_denoms");
+pub const SHARE_DENOM_SEQ: Item<u64> = Item::new("share_denom_seq");
+
+/// Subdenoms are numbered, as tokenfactory caps them at 44 characters and has no room for the
+/// collection address
+pub fn next_share_subdenom(storage: &mut dyn Storage) -> StdResult<String> {
+  let id = SHARE_DENOM_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+  SHARE_DENOM_SEQ.save(storage, &id)?;
+  Ok(format!("vault{}", id))
+}
+
+/// Draft basket vault collecting NFTs until its creator finalizes it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Basket {
+  pub creator: Addr,
+  pub collection: Addr,
+  pub token_ids: Vec<String>,
+}
+
+pub const BASKETS: Map<u64, Basket> = Map::new("baskets");
+pub const BASKET_ID_SEQ: Item<u64> = Item::new("basket_id_seq");
+
+pub fn next_basket_id(storage: &mut dyn Storage) -> StdResult<u64> {
+  let id = BASKET_ID_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+  BASKET_ID_SEQ.save(storage, &id)?;
+  Ok(id)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Shotgun {
+  pub initiator: Addr,
+  pub price_per_share: Uint128,
+  /// Shares of the initiator held in escrow
+  pub shares: Uint128,
+  /// Funds escrowed to buy out every other share holder
+  pub escrow: Uint128,
+  /// Buyout denom of the escrow, which a counter offer pays in as well
+  pub denom: String,
+  pub deadline: Timestamp,
+  /// Auction pause time already added to `deadline`
+  #[serde(default)]
+  pub paused_seconds: u64,
+}
+
+impl Shotgun {
+  /// Pushes the deadline back by the time auctions were paused since it was last set
+  pub fn resume(&mut self, paused_seconds: u64) {
+    resume(&mut self.deadline, &mut self.paused_seconds, paused_seconds);
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DutchAuction {
+  pub start_price: Uint128,
+  pub floor_price: Uint128,
+  pub start_time: Timestamp,
+  pub duration: u64,
+  pub decay: PriceDecay,
+  /// Auction pause time already added to `start_time`
+  #[serde(default)]
+  pub paused_seconds: u64,
+}
+
+impl DutchAuction {
+  /// Holds the price while auctions are paused by pushing the start time back
+  pub fn resume(&mut self, paused_seconds: u64) {
+    resume(
+      &mut self.start_time,
+      &mut self.paused_seconds,
+      paused_seconds,
+    );
+  }
+
+  pub fn price_at(&self, time: Timestamp) -> Uint128 {
+    let elapsed = time.seconds().saturating_sub(self.start_time.seconds());
+    if elapsed >= self.duration {
+      return self.floor_price;
+    }
+
+    let premium = self.start_price - self.floor_price;
+    let remaining = match self.decay {
+      PriceDecay::Linear => premium.multiply_ratio(self.duration - elapsed, self.duration),
+      PriceDecay::Exponential { half_life } => {
+        let halvings = elapsed / half_life;
+        if halvings >= 128 {
+          Uint128::zero()
+        } else {
+          // interpolate linearly within the current half-life
+          let current = premium >> (halvings as u32);
+          let next = current >> 1;
+          current - (current - next).multiply_ratio(elapsed % half_life, half_life)
+        }
+      }
+    };
+    self.floor_price + remaining
+  }
+}
+
+/// Assets held on behalf of the share holders of a vault, next to its NFT
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct VaultAssets {
+  pub native: Vec<Coin>,
+  pub cw20: Vec<Cw20CoinVerified>,
+}
+
+impl VaultAssets {
+  pub fn is_empty(&self) -> bool {
+    self.native.is_empty() && self.cw20.is_empty()
+  }
+
+  pub fn add_native(&mut self, coin: Coin) {
+    match self.native.iter_mut().find(|c| c.denom == coin.denom) {
+      Some(existing) => existing.amount += coin.amount,
+      None => self.native.push(coin),
+    }
+  }
+
+  pub fn add_cw20(&mut self, address: Addr, amount: Uint128) {
+    match self.cw20.iter_mut().find(|c| c.address == address) {
+      Some(existing) => existing.amount += amount,
+      None => self.cw20.push(Cw20CoinVerified { address, amount }),
+    }
+  }
+
+  /// Takes `amount / total` of every asset out of the vault, rounding down
+  pub fn split(&mut self, amount: Uint128, total: Uint128) -> VaultAssets {
+    let mut share = VaultAssets::default();
+    for coin in self.native.iter_mut() {
+      let part = coin.amount.multiply_ratio(amount, total);
+      coin.amount -= part;
+      if !part.is_zero() {
+        share
+          .native
+          .push(Coin::new(part.u128(), coin.denom.clone()));
+      }
+    }
+    for coin in self.cw20.iter_mut() {
+      let part = coin.amount.multiply_ratio(amount, total);
+      coin.amount -= part;
+      if !part.is_zero() {
+        share.cw20.push(Cw20CoinVerified {
+          address: coin.address.clone(),
+          amount: part,
+        });
+      }
+    }
+    self.native.retain(|c| !c.amount.is_zero());
+    self.cw20.retain(|c| !c.amount.is_zero());
+    share
+  }
+}
+
 pub const CONFIG: Item<Config> = Item::new("config");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Lock {
+  pub amount: Uint128,
+  /// Shares stay locked until every proposal voted on with them has ended
+  pub unlocks_at: Timestamp,
+}
+
+/// Shares locked as voting power, keyed by share token and holder
+pub const LOCKS: Map<(&str, &Addr), Lock> = Map::new("locks");
+
+/// Unset until the owner first pauses anything
+pub const PAUSED: Item<PauseState> = Item::new("paused");
+
+/// Time auctions have spent paused, by which running auctions are pushed back so that no deadline
+/// passes and no price decays while bids and counters are blocked
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct AuctionPauseClock {
+  /// Seconds of every finished pause
+  pub paused_seconds: u64,
+  /// Start of the current pause, if auctions are paused
+  pub paused_since: Option<Timestamp>,
+}
+
+pub const AUCTION_PAUSE_CLOCK: Item<AuctionPauseClock> = Item::new("auction_pause_clock");
+
+/// Total seconds auctions have been paused up to `time`
+pub fn auction_paused_seconds(storage: &dyn Storage, time: Timestamp) -> StdResult<u64> {
+  let clock = AUCTION_PAUSE_CLOCK.may_load(storage)?.unwrap_or_default();
+  let current = clock
+    .paused_since
+    .map(|since| time.seconds().saturating_sub(since.seconds()))
+    .unwrap_or_default();
+  Ok(clock.paused_seconds + current)
+}
+
+/// Unset until the owner first sets it, leaving every collection open
+pub const COLLECTION_MODE: Item<CollectionMode> = Item::new("collection_mode");
+pub const ALLOWLIST: Map<&Addr, Empty> = Map::new("allowlist");
+pub const DENYLIST: Map<&Addr, Empty> = Map::new("denylist");
+
+/// Flat fees prepaid by each sender, keyed by denom
+pub const FEE_CREDITS: Map<(&Addr, &str), Uint128> = Map::new("fee_credits");
+
+pub const RESERVE_PRICE_VOTES: Map<(&str, &Addr), ReservePriceVote> =
+  Map::new("reserve_price_votes");
+/// Running buyout auctions, keyed by share token
+pub const AUCTIONS: Map<&str, Auction> = Map::new("auctions");
+/// Open shotgun offers, keyed by share token
+pub const SHOTGUNS: Map<&str, Shotgun> = Map::new("shotguns");
+/// Dutch auctions opted into at fractionalization, keyed by share token
+pub const DUTCH_AUCTIONS: Map<&str, DutchAuction> = Map::new("dutch_auctions");
+/// Buyout proceeds and deposits left to be redeemed by share holders, keyed by share token
+pub const VAULT_ASSETS: Map<&str, VaultAssets> = Map::new("vault_assets");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Proposal {
+  pub cw20: Addr,
+  pub proposer: Addr,
+  pub action: ProposalAction,
+  pub yes_votes: Uint128,
+  pub no_votes: Uint128,
+  pub abstain_votes: Uint128,
+  pub end_time: Timestamp,
+  pub status: ProposalStatus,
+}
+
+pub struct ProposalIndexes<'a> {
+  pub cw20: MultiIndex<'a, Addr, Proposal, u64>,
+}
+
+impl<'a> IndexList<Proposal> for ProposalIndexes<'a> {
+  fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Proposal>> + '_> {
+    let v: Vec<&dyn Index<Proposal>> = vec![&self.cw20];
+    Box::new(v.into_iter())
+  }
+}
+
+/// Proposals of every vault, keyed by ID
+pub fn proposals<'a>() -> IndexedMap<'a, u64, Proposal, ProposalIndexes<'a>> {
+  let indexes = ProposalIndexes {
+    cw20: MultiIndex::new(
+      |_pk, proposal| proposal.cw20.clone(),
+      "proposals",
+      "proposals__cw20",
+    ),
+  };
+  IndexedMap::new("proposals", indexes)
+}
+
+pub const PROPOSAL_ID_SEQ: Item<u64> = Item::new("proposal_id_seq");
+
+pub fn next_proposal_id(storage: &mut dyn Storage) -> StdResult<u64> {
+  let id = PROPOSAL_ID_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+  PROPOSAL_ID_SEQ.save(storage, &id)?;
+  Ok(id)
+}
+
+/// Voters of each proposal, with the locked shares they voted with
+pub const BALLOTS: Map<(u64, &Addr), Uint128> = Map::new("ballots");
+
+/// Reserve prices set by a passed proposal, keyed by share token
+pub const RESERVE_PRICE_OVERRIDES: Map<&str, Uint128> = Map::new("reserve_price_overrides");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Sale {
+  pub buyer: Addr,
+  pub price: Uint128,
+}
+
+/// Sales approved by a passed proposal, keyed by share token
+pub const SALES: Map<&str, Sale> = Map::new("sales");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingTransfer {
+  pub recipient: Addr,
+  pub token: Cw20CoinVerified,
+}
+
+/// Deposited cw20s being paid out of a vault, keyed by the reply ID of their transfer
+pub const PENDING_TRANSFERS: Map<u64, PendingTransfer> = Map::new("pending_transfers");
+
+/// Deposited cw20s whose payout failed, claimable by the recipient, keyed by recipient and token
+pub const ASSET_CLAIMS: Map<(&Addr, &Addr), Uint128> = Map::new("asset_claims");
+
+/// NFTs awaiting their share token, keyed by the reply ID of the cw20 instantiation
+pub const PENDING_VAULTS: Map<u64, PendingVault> = Map::new("pending_vaults");
+pub const REPLY_ID_SEQ: Item<u64> = Item::new("reply_id_seq");
+
+pub fn next_reply_id(storage: &mut dyn Storage) -> StdResult<u64> {
+  let id = REPLY_ID_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+  REPLY_ID_SEQ.save(storage, &id)?;
+  Ok(id)
+}